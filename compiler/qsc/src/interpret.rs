@@ -177,6 +177,7 @@ impl Interpreter {
                     // will still respect the selected profile. This also
                     // matches the behavior of the simulator.
                     base_profile: false,
+                    ..Default::default()
                 }),
             ),
             quantum_seed: None,
@@ -334,6 +335,7 @@ impl Interpreter {
         let mut out = GenericReceiver::new(&mut sink);
         let mut sim = CircuitBuilder::new(CircuitConfig {
             base_profile: self.capabilities.is_empty(),
+            ..Default::default()
         });
 
         let entry_expr = match entry {
@@ -7,8 +7,12 @@ use super::{CircuitEntryPoint, Debugger, Interpreter};
 use crate::target::Profile;
 use expect_test::expect;
 use miette::Diagnostic;
+use qsc_circuit::{Builder as CircuitBuilder, Config as CircuitConfig};
 use qsc_data_structures::language_features::LanguageFeatures;
-use qsc_eval::output::GenericReceiver;
+use qsc_eval::{
+    backend::{Chain as BackendChain, SparseSim},
+    output::GenericReceiver,
+};
 use qsc_frontend::compile::SourceMap;
 use qsc_passes::PackageType;
 
@@ -918,3 +922,179 @@ mod debugger_stepping {
         .assert_eq(&circs);
     }
 }
+
+#[test]
+fn operations_listed_by_enclosing_operation_name() {
+    let mut interpreter = interpreter(
+        r"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {
+                    use qs = Qubit[2];
+                    Prepare(qs[0]);
+                    Entangle(qs);
+                }
+
+                operation Prepare(q : Qubit) : Unit {
+                    H(q);
+                }
+
+                operation Entangle(qs : Qubit[]) : Unit {
+                    CNOT(qs[0], qs[1]);
+                }
+            }
+        ",
+        Profile::Unrestricted,
+    );
+
+    let circ = interpreter
+        .circuit(CircuitEntryPoint::EntryPoint)
+        .expect("circuit generation should succeed");
+
+    assert_eq!(circ.operations_in_region("Prepare"), vec![0]);
+    assert_eq!(circ.operations_in_region("Entangle"), vec![1]);
+    assert_eq!(circ.operations_in_region("Main"), vec![0, 1]);
+}
+
+#[test]
+fn regions_of_failed_calls_are_exited() {
+    let mut interpreter = interpreter(
+        r#"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {}
+
+                operation Fails(q : Qubit) : Unit {
+                    H(q);
+                    fail "failure";
+                }
+            }
+        "#,
+        Profile::Unrestricted,
+    );
+
+    let mut sim = CircuitBuilder::new(CircuitConfig::default());
+    let mut out = std::io::sink();
+    let mut r = GenericReceiver::new(&mut out);
+
+    interpreter
+        .run_with_sim(&mut sim, &mut r, "{ use q = Qubit(); Test.Fails(q); }")
+        .expect("run should succeed")
+        .expect_err("evaluation should fail");
+    interpreter
+        .run_with_sim(&mut sim, &mut r, "{ use q = Qubit(); X(q); }")
+        .expect("run should succeed")
+        .expect("evaluation should succeed");
+
+    // The X gate is applied after the call to `Fails` failed, so it is not within its region.
+    let circ = sim.snapshot();
+    assert_eq!(circ.operations_in_region("Fails"), vec![0]);
+    assert_eq!(circ.operations_in_region("X"), vec![1]);
+}
+
+#[test]
+fn stored_measurement_reaches_chained_circuit_builder() {
+    let mut interpreter = interpreter(
+        r"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {}
+            }
+        ",
+        Profile::Unrestricted,
+    );
+
+    let mut sim = BackendChain::new(
+        CircuitBuilder::new(CircuitConfig::default()),
+        CircuitBuilder::new(CircuitConfig::default()),
+    );
+    let mut out = std::io::sink();
+    let mut r = GenericReceiver::new(&mut out);
+
+    interpreter
+        .run_with_sim(
+            &mut sim,
+            &mut r,
+            "{ use (q0, q1) = (Qubit(), Qubit()); H(q0); let r = M(q0); }",
+        )
+        .expect("run should succeed")
+        .expect("evaluation should succeed");
+
+    // The binding of `r` is only known to the chained builder if the chain forwards it.
+    sim.chained.begin_classical_control("r");
+    interpreter
+        .run_with_sim(
+            &mut sim,
+            &mut r,
+            "{ use (q0, q1) = (Qubit(), Qubit()); X(q1); }",
+        )
+        .expect("run should succeed")
+        .expect("evaluation should succeed");
+    sim.chained.end_classical_control();
+
+    expect![[r#"
+        q_0    ── H ──── M ─────────
+                         ╘═════ ● ══
+        q_1    ──────────────── X ──
+    "#]]
+    .assert_eq(&sim.chained.snapshot().to_string());
+}
+
+#[test]
+fn stored_measurement_array_elements_reach_circuit_builder() {
+    let mut interpreter = interpreter(
+        r"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {}
+            }
+        ",
+        Profile::Unrestricted,
+    );
+
+    let mut sim = CircuitBuilder::new(CircuitConfig::default());
+    let mut out = std::io::sink();
+    let mut r = GenericReceiver::new(&mut out);
+
+    interpreter
+        .run_with_sim(
+            &mut sim,
+            &mut r,
+            "{ use (q0, q1) = (Qubit(), Qubit()); let rs = [M(q0), M(q1)]; }",
+        )
+        .expect("run should succeed")
+        .expect("evaluation should succeed");
+
+    assert!(!sim.has_classical_var("rs"));
+    assert!(sim.has_classical_var("rs[0]"));
+    assert!(sim.has_classical_var("rs[1]"));
+}
+
+#[test]
+fn simulated_measurement_does_not_reach_chained_circuit_builder() {
+    let mut interpreter = interpreter(
+        r"
+            namespace Test {
+                @EntryPoint()
+                operation Main() : Unit {}
+            }
+        ",
+        Profile::Unrestricted,
+    );
+
+    let mut sim = BackendChain::new(
+        SparseSim::new(),
+        CircuitBuilder::new(CircuitConfig::default()),
+    );
+    let mut out = std::io::sink();
+    let mut r = GenericReceiver::new(&mut out);
+
+    interpreter
+        .run_with_sim(&mut sim, &mut r, "{ use q = Qubit(); let r = M(q); }")
+        .expect("run should succeed")
+        .expect("evaluation should succeed");
+
+    // The simulator measures results as values rather than identifiers, so the binding of `r` cannot be related to
+    // the measurement recorded by the chained builder.
+    assert!(!sim.chained.has_classical_var("r"));
+}
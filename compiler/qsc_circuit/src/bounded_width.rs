@@ -0,0 +1,221 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use num_bigint::BigUint;
+use num_complex::Complex;
+use qsc_eval::{backend::Backend, val::Value};
+use rustc_hash::FxHashSet;
+
+/// Backend wrapper that keeps track of how many distinct qubits get allocated and flags
+/// when the circuit grows wider than the given limit, e.g. to avoid rendering circuits
+/// that are too wide to be displayed.
+/// Once the limit is exceeded, gates are no longer forwarded to the inner backend, so it
+/// stops recording a circuit that will not be used. Calls that produce values (e.g. qubit
+/// allocations and measurements) are still forwarded so that the program keeps running.
+pub struct BoundedWidthBackend<B> {
+    pub inner: B,
+    max_width: usize,
+    qubits: FxHashSet<usize>,
+}
+
+impl<B> BoundedWidthBackend<B>
+where
+    B: Backend,
+{
+    #[must_use]
+    pub fn new(inner: B, max_width: usize) -> Self {
+        Self {
+            inner,
+            max_width,
+            qubits: FxHashSet::default(),
+        }
+    }
+
+    /// The number of distinct qubits allocated so far.
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.qubits.len()
+    }
+
+    /// Whether more distinct qubits than the limit have been allocated.
+    #[must_use]
+    pub fn width_exceeded(&self) -> bool {
+        self.width() > self.max_width
+    }
+}
+
+impl<B> Backend for BoundedWidthBackend<B>
+where
+    B: Backend,
+{
+    type ResultType = B::ResultType;
+
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        if !self.width_exceeded() {
+            self.inner.ccx(ctl0, ctl1, q);
+        }
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        if !self.width_exceeded() {
+            self.inner.cx(ctl, q);
+        }
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        if !self.width_exceeded() {
+            self.inner.cy(ctl, q);
+        }
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        if !self.width_exceeded() {
+            self.inner.cz(ctl, q);
+        }
+    }
+
+    fn h(&mut self, q: usize) {
+        if !self.width_exceeded() {
+            self.inner.h(q);
+        }
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        self.inner.m(q)
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        self.inner.mresetz(q)
+    }
+
+    fn reset(&mut self, q: usize) {
+        if !self.width_exceeded() {
+            self.inner.reset(q);
+        }
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        if !self.width_exceeded() {
+            self.inner.rx(theta, q);
+        }
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        if !self.width_exceeded() {
+            self.inner.rxx(theta, q0, q1);
+        }
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        if !self.width_exceeded() {
+            self.inner.ry(theta, q);
+        }
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        if !self.width_exceeded() {
+            self.inner.ryy(theta, q0, q1);
+        }
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        if !self.width_exceeded() {
+            self.inner.rz(theta, q);
+        }
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        if !self.width_exceeded() {
+            self.inner.rzz(theta, q0, q1);
+        }
+    }
+
+    fn sadj(&mut self, q: usize) {
+        if !self.width_exceeded() {
+            self.inner.sadj(q);
+        }
+    }
+
+    fn s(&mut self, q: usize) {
+        if !self.width_exceeded() {
+            self.inner.s(q);
+        }
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        if !self.width_exceeded() {
+            self.inner.swap(q0, q1);
+        }
+    }
+
+    fn tadj(&mut self, q: usize) {
+        if !self.width_exceeded() {
+            self.inner.tadj(q);
+        }
+    }
+
+    fn t(&mut self, q: usize) {
+        if !self.width_exceeded() {
+            self.inner.t(q);
+        }
+    }
+
+    fn x(&mut self, q: usize) {
+        if !self.width_exceeded() {
+            self.inner.x(q);
+        }
+    }
+
+    fn y(&mut self, q: usize) {
+        if !self.width_exceeded() {
+            self.inner.y(q);
+        }
+    }
+
+    fn z(&mut self, q: usize) {
+        if !self.width_exceeded() {
+            self.inner.z(q);
+        }
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        let q = self.inner.qubit_allocate();
+        self.qubits.insert(q);
+        q
+    }
+
+    fn qubit_release(&mut self, q: usize) {
+        self.inner.qubit_release(q);
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        self.inner.capture_quantum_state()
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        self.inner.qubit_is_zero(q)
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        self.inner.custom_intrinsic(name, arg)
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.inner.set_seed(seed);
+    }
+
+    fn set_classical(&mut self, name: &str, result: usize) {
+        self.inner.set_classical(name, result);
+    }
+
+    fn enter_region(&mut self, label: &str, is_library_call: bool) {
+        self.inner.enter_region(label, is_library_call);
+    }
+
+    fn exit_region(&mut self) {
+        self.inner.exit_region();
+    }
+}
@@ -0,0 +1,37 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::*;
+use crate::{Builder, Config};
+
+#[test]
+fn width_exceeded_once_more_qubits_than_limit_are_allocated() {
+    let mut backend = BoundedWidthBackend::new(Builder::new(Config::default()), 3);
+    let qubits = (0..3).map(|_| backend.qubit_allocate()).collect::<Vec<_>>();
+    for &q in &qubits {
+        backend.h(q);
+    }
+    assert!(!backend.width_exceeded());
+
+    let q = backend.qubit_allocate();
+    backend.x(q);
+    assert_eq!(backend.width(), 4);
+    assert!(backend.width_exceeded());
+
+    // The inner backend stops recording gates once the limit is exceeded.
+    backend.h(qubits[0]);
+    let circuit = backend.inner.finish(&Value::unit());
+    assert_eq!(circuit.operations.len(), 3);
+    assert!(circuit.operations.iter().all(|op| op.gate == "H"));
+}
+
+#[test]
+fn reused_qubit_counts_once_towards_width() {
+    let mut backend = BoundedWidthBackend::new(Builder::new(Config::default()), 1);
+    let q = backend.qubit_allocate();
+    backend.qubit_release(q);
+    let q = backend.qubit_allocate();
+    backend.h(q);
+    assert_eq!(backend.width(), 1);
+    assert!(!backend.width_exceeded());
+}
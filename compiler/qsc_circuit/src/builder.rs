@@ -1,8 +1,11 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+#[cfg(test)]
+mod tests;
+
 use crate::{
-    circuit::{Circuit, Operation, Register},
+    circuit::{Circuit, ClassicalWire, Operation, Register, KET_ZERO},
     Config,
 };
 use num_bigint::BigUint;
@@ -10,6 +13,7 @@ use num_complex::Complex;
 use qsc_codegen::remapper::{HardwareId, Remapper};
 use qsc_data_structures::index_map::IndexMap;
 use qsc_eval::{backend::Backend, val::Value};
+use rustc_hash::FxHashMap;
 use std::{fmt::Write, mem::take, rc::Rc};
 
 /// Backend implementation that builds a circuit representation.
@@ -17,6 +21,19 @@ pub struct Builder {
     circuit: Circuit,
     config: Config,
     remapper: Remapper,
+    prep_block_start: Option<usize>,
+    /// The classical register holding the measurement result stored in each classical variable
+    classical_vars: FxHashMap<String, Register>,
+    classical_control: Option<Register>,
+    live_qubits: usize,
+    peak_live_qubits: usize,
+    /// The labels of the source regions currently entered, innermost last
+    regions: Vec<Rc<str>>,
+    /// The region depth and the index of the first operation of the library call
+    /// currently being collapsed into a single operation
+    collapsed_region: Option<(usize, usize)>,
+    /// The region each deferred base profile measurement was made in, by qubit
+    deferred_regions: FxHashMap<usize, String>,
 }
 
 impl Backend for Builder {
@@ -55,7 +72,7 @@ impl Backend for Builder {
     fn m(&mut self, q: usize) -> Self::ResultType {
         if self.config.base_profile {
             // defer the measurement and reset the qubit
-            self.remapper.mreset(q)
+            self.defer_measurement(q)
         } else {
             let mapped_q = self.map(q);
             // In the Circuit schema, result id is per-qubit
@@ -64,7 +81,11 @@ impl Backend for Builder {
             // remapping any qubits, but it's handy for keeping track of measurements
             let id = self.remapper.m(q);
 
-            self.push_gate(measurement_gate(mapped_q.0, res_id));
+            self.push_gate(measurement_gate(
+                self.config.measurement_symbol,
+                mapped_q.0,
+                res_id,
+            ));
             id
         }
     }
@@ -72,7 +93,7 @@ impl Backend for Builder {
     fn mresetz(&mut self, q: usize) -> Self::ResultType {
         if self.config.base_profile {
             // defer the measurement
-            self.remapper.mreset(q)
+            self.defer_measurement(q)
         } else {
             let mapped_q = self.map(q);
             // In the Circuit schema, result id is per-qubit
@@ -84,7 +105,11 @@ impl Backend for Builder {
             // Ideally MResetZ would be atomic but we don't currently have
             // a way to visually represent that. So decompose it into
             // a measurement and a reset gate.
-            self.push_gate(measurement_gate(mapped_q.0, res_id));
+            self.push_gate(measurement_gate(
+                self.config.measurement_symbol,
+                mapped_q.0,
+                res_id,
+            ));
             self.push_gate(gate(KET_ZERO, [mapped_q]));
             id
         }
@@ -174,10 +199,13 @@ impl Backend for Builder {
     }
 
     fn qubit_allocate(&mut self) -> usize {
+        self.live_qubits += 1;
+        self.peak_live_qubits = self.peak_live_qubits.max(self.live_qubits);
         self.remapper.qubit_allocate()
     }
 
     fn qubit_release(&mut self, q: usize) {
+        self.live_qubits -= 1;
         self.remapper.qubit_release(q);
     }
 
@@ -208,6 +236,44 @@ impl Backend for Builder {
         ));
         Some(Ok(Value::unit()))
     }
+
+    fn set_classical(&mut self, name: &str, result: usize) {
+        // A result that was not measured by this builder cannot be referenced, so the variable is forgotten instead of
+        // keeping a stale register from a previous binding.
+        match self.result_register(result) {
+            Some(register) => {
+                self.classical_vars.insert(name.to_string(), register);
+            }
+            None => {
+                self.classical_vars.remove(name);
+            }
+        }
+    }
+
+    fn enter_region(&mut self, label: &str, is_library_call: bool) {
+        self.regions.push(label.into());
+        if self.config.collapse_library_ops && is_library_call && self.collapsed_region.is_none() {
+            self.collapsed_region = Some((self.regions.len(), self.circuit.operations.len()));
+        }
+    }
+
+    fn exit_region(&mut self) {
+        let Some(label) = self.regions.pop() else {
+            return;
+        };
+        if let Some((depth, start)) = self.collapsed_region {
+            if depth == self.regions.len() + 1 {
+                self.collapsed_region = None;
+                // Library operations that emit a single gate are left as they are
+                let children = self.circuit.operations.split_off(start);
+                if children.len() > 1 {
+                    self.push_gate(grouped_gate(&label, children));
+                } else {
+                    self.circuit.operations.extend(children);
+                }
+            }
+        }
+    }
 }
 
 impl Builder {
@@ -217,9 +283,96 @@ impl Builder {
             circuit: Circuit::default(),
             config,
             remapper: Remapper::default(),
+            prep_block_start: None,
+            classical_vars: FxHashMap::default(),
+            classical_control: None,
+            live_qubits: 0,
+            peak_live_qubits: 0,
+            regions: Vec::new(),
+            collapsed_region: None,
+            deferred_regions: FxHashMap::default(),
         }
     }
 
+    /// Marks the start of a state preparation block. All the gates pushed
+    /// until the block is ended get collapsed into a single operation.
+    pub fn begin_prep_block(&mut self) {
+        assert!(
+            self.prep_block_start.is_none(),
+            "state preparation blocks cannot be nested"
+        );
+        self.prep_block_start = Some(self.circuit.operations.len());
+    }
+
+    /// Marks the end of a state preparation block, collapsing all the gates
+    /// pushed since the block started into a single "Prepare" operation
+    /// that targets every qubit used in the block.
+    pub fn end_prep_block(&mut self) {
+        let start = self
+            .prep_block_start
+            .take()
+            .expect("state preparation block should have been started");
+        let children = self.circuit.operations.split_off(start);
+        if children.is_empty() {
+            return;
+        }
+
+        self.push_gate(grouped_gate("Prepare", children));
+    }
+
+    /// Whether the measurement result stored in the given classical variable is known, which is required to start a
+    /// block of gates conditioned on it.
+    #[must_use]
+    pub fn has_classical_var(&self, name: &str) -> bool {
+        self.classical_vars.contains_key(name)
+    }
+
+    /// Marks the start of a block of gates that are conditioned on the measurement result stored in the
+    /// given classical variable. All the gates pushed until the block is ended are controlled on the
+    /// classical register of that result.
+    pub fn begin_classical_control(&mut self, name: &str) {
+        assert!(
+            self.classical_control.is_none(),
+            "classically controlled blocks cannot be nested"
+        );
+        let register = self
+            .classical_vars
+            .get(name)
+            .expect("classical variable should have been set")
+            .clone();
+        self.classical_control = Some(register);
+    }
+
+    /// Marks the end of a block of classically controlled gates.
+    pub fn end_classical_control(&mut self) {
+        self.classical_control
+            .take()
+            .expect("classically controlled block should have been started");
+    }
+
+    /// Pushes a rotation gate whose angle is a symbolic expression rather than an evaluated value,
+    /// which is used when the circuit is built before angles are substituted.
+    pub fn symbolic_rotation<const N: usize>(
+        &mut self,
+        name: &str,
+        theta: &str,
+        qubits: [usize; N],
+    ) {
+        let targets = qubits.map(|q| self.map(q));
+        self.push_gate(symbolic_rotation_gate(name, theta, targets));
+    }
+
+    /// Attaches a note to the last operation pushed, which is used to annotate diagrams
+    /// (e.g. to mark a gate as "error-corrected" or "physical").
+    pub fn annotate_last(&mut self, note: &str) {
+        let op = self
+            .circuit
+            .operations
+            .last_mut()
+            .expect("an operation should have been pushed");
+        op.note = Some(note.into());
+    }
+
     #[must_use]
     pub fn snapshot(&self) -> Circuit {
         let circuit = self.circuit.clone();
@@ -236,10 +389,61 @@ impl Builder {
         self.remapper.map(qubit)
     }
 
-    fn push_gate(&mut self, gate: Operation) {
+    fn push_gate(&mut self, mut gate: Operation) {
+        if let Some(register) = &self.classical_control {
+            gate.is_controlled = true;
+            gate.controls.push(register.clone());
+        }
+        if !self.regions.is_empty() {
+            gate.region = Some(self.regions.join("/"));
+        }
+        gate.style = self.gate_style(&gate);
         self.circuit.operations.push(gate);
     }
 
+    /// Finds the style configured for the gate, which is keyed on the gate name and its number of quantum controls
+    /// (e.g. CNOT is the gate "X" with one control). Classical controls and measured qubits don't count as controls.
+    fn gate_style(&self, gate: &Operation) -> Option<String> {
+        let num_controls = if gate.is_measurement {
+            0
+        } else {
+            gate.controls.iter().filter(|c| c.c_id.is_none()).count()
+        };
+        self.config
+            .gate_styles
+            .iter()
+            .find(|(name, controls, _)| *name == gate.gate && *controls == num_controls)
+            .map(|(_, _, style)| (*style).to_string())
+    }
+
+    /// Defers the measurement of the qubit to the end of the circuit, remembering the region it was made in.
+    fn defer_measurement(&mut self, q: usize) -> usize {
+        let id = self.remapper.mreset(q);
+        if !self.regions.is_empty() {
+            let (qubit, _) = self
+                .remapper
+                .measurements()
+                .last()
+                .expect("measurement should have been recorded");
+            let qubit = qubit.0;
+            self.deferred_regions.insert(qubit, self.regions.join("/"));
+        }
+        id
+    }
+
+    /// Finds the classical register of the measurement with the given result id.
+    /// In the Circuit schema, result ids are per-qubit.
+    fn result_register(&self, result: usize) -> Option<Register> {
+        let (qubit, _) = self.remapper.measurements().find(|(_, id)| *id == result)?;
+        let c_id = self
+            .remapper
+            .measurements()
+            .take_while(|(_, id)| *id != result)
+            .filter(|(q, _)| q.0 == qubit.0)
+            .count();
+        Some(Register::classical(qubit.0, c_id))
+    }
+
     fn num_measurements_by_qubit(&self) -> IndexMap<usize, usize> {
         self.remapper.measurements().fold(
             IndexMap::default(),
@@ -263,13 +467,22 @@ impl Builder {
     }
 
     fn finish_circuit(&self, mut circuit: Circuit) -> Circuit {
+        if self.config.drop_global_phase {
+            circuit = circuit.drop_global_phase();
+        }
+
         let by_qubit = self.num_measurements_by_qubit();
 
         // add deferred measurements
+        // In base profile mode, measurements are only tracked by the remapper and never pushed as they happen,
+        // so each measurement is rendered exactly once here, however many times a snapshot is taken.
         if self.config.base_profile {
             for (qubit, _) in &by_qubit {
                 // guaranteed one measurement per qubit, so result is always 0
-                circuit.operations.push(measurement_gate(qubit, 0));
+                let mut gate = measurement_gate(self.config.measurement_symbol, qubit, 0);
+                gate.region = self.deferred_regions.get(&qubit).cloned();
+                gate.style = self.gate_style(&gate);
+                circuit.operations.push(gate);
             }
         }
 
@@ -282,6 +495,19 @@ impl Builder {
             });
         }
 
+        // collect all measurement results onto a single classical wire
+        if self.config.show_classical_wires && !by_qubit.is_empty() {
+            let results = self
+                .remapper
+                .measurements()
+                .filter_map(|(_, result)| self.result_register(*result))
+                .collect();
+            circuit
+                .classical_wires
+                .push(ClassicalWire { id: 0, results });
+        }
+
+        circuit.peak_live_qubits = Some(self.peak_live_qubits);
         circuit
     }
 
@@ -364,9 +590,6 @@ impl Builder {
     }
 }
 
-#[allow(clippy::unicode_not_nfc)]
-static KET_ZERO: &str = "|0〉";
-
 fn gate<const N: usize>(name: &str, targets: [HardwareId; N]) -> Operation {
     Operation {
         gate: name.into(),
@@ -377,6 +600,9 @@ fn gate<const N: usize>(name: &str, targets: [HardwareId; N]) -> Operation {
         controls: vec![],
         targets: targets.iter().map(|q| Register::quantum(q.0)).collect(),
         children: vec![],
+        note: None,
+        region: None,
+        style: None,
     }
 }
 
@@ -390,6 +616,9 @@ fn adjoint_gate<const N: usize>(name: &str, targets: [HardwareId; N]) -> Operati
         controls: vec![],
         targets: targets.iter().map(|q| Register::quantum(q.0)).collect(),
         children: vec![],
+        note: None,
+        region: None,
+        style: None,
     }
 }
 
@@ -407,12 +636,15 @@ fn controlled_gate<const M: usize, const N: usize>(
         controls: controls.iter().map(|q| Register::quantum(q.0)).collect(),
         targets: targets.iter().map(|q| Register::quantum(q.0)).collect(),
         children: vec![],
+        note: None,
+        region: None,
+        style: None,
     }
 }
 
-fn measurement_gate(qubit: usize, result: usize) -> Operation {
+fn measurement_gate(symbol: &str, qubit: usize, result: usize) -> Operation {
     Operation {
-        gate: "Measure".into(),
+        gate: symbol.into(),
         display_args: None,
         is_controlled: false,
         is_adjoint: false,
@@ -420,19 +652,60 @@ fn measurement_gate(qubit: usize, result: usize) -> Operation {
         controls: vec![Register::quantum(qubit)],
         targets: vec![Register::classical(qubit, result)],
         children: vec![],
+        note: None,
+        region: None,
+        style: None,
     }
 }
 
 fn rotation_gate<const N: usize>(name: &str, theta: f64, targets: [HardwareId; N]) -> Operation {
+    symbolic_rotation_gate(name, &format!("{theta:.4}"), targets)
+}
+
+fn symbolic_rotation_gate<const N: usize>(
+    name: &str,
+    theta: &str,
+    targets: [HardwareId; N],
+) -> Operation {
     Operation {
         gate: name.into(),
-        display_args: Some(format!("{theta:.4}")),
+        display_args: Some(theta.into()),
         is_controlled: false,
         is_adjoint: false,
         is_measurement: false,
         controls: vec![],
         targets: targets.iter().map(|q| Register::quantum(q.0)).collect(),
         children: vec![],
+        note: None,
+        region: None,
+        style: None,
+    }
+}
+
+/// An operation that groups the given operations into a single one that targets
+/// every qubit they act on.
+fn grouped_gate(name: &str, children: Vec<Operation>) -> Operation {
+    let mut qubits = children
+        .iter()
+        .flat_map(|op| op.controls.iter().chain(op.targets.iter()))
+        .filter(|reg| reg.c_id.is_none())
+        .map(|reg| reg.q_id)
+        .collect::<Vec<_>>();
+    qubits.sort_unstable();
+    qubits.dedup();
+
+    Operation {
+        gate: name.into(),
+        display_args: None,
+        is_controlled: false,
+        is_adjoint: false,
+        is_measurement: false,
+        controls: vec![],
+        targets: qubits.into_iter().map(Register::quantum).collect(),
+        children,
+        note: None,
+        region: None,
+        style: None,
     }
 }
 
@@ -446,5 +719,8 @@ fn custom_gate(name: &str, targets: &[HardwareId], display_args: Option<String>)
         controls: vec![],
         targets: targets.iter().map(|q| Register::quantum(q.0)).collect(),
         children: vec![],
+        note: None,
+        region: None,
+        style: None,
     }
 }
@@ -0,0 +1,354 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::*;
+use expect_test::expect;
+
+#[test]
+fn prep_block_collapses_gates_into_single_operation() {
+    let mut builder = Builder::new(Config::default());
+    let q0 = builder.qubit_allocate();
+    let q1 = builder.qubit_allocate();
+    builder.begin_prep_block();
+    builder.ry(0.5, q0);
+    builder.cx(q0, q1);
+    builder.end_prep_block();
+    builder.h(q1);
+
+    let circuit = builder.finish(&Value::unit());
+    assert_eq!(circuit.operations.len(), 2);
+    let prep = &circuit.operations[0];
+    assert_eq!(prep.gate, "Prepare");
+    expect![[r#"
+        [
+            "ry",
+            "X",
+        ]
+    "#]]
+    .assert_debug_eq(
+        &prep
+            .children
+            .iter()
+            .map(|op| op.gate.as_str())
+            .collect::<Vec<_>>(),
+    );
+    expect![[r"
+        q_0     Prepare ───────
+        q_1     Prepare ── H ──
+    "]]
+    .assert_eq(&circuit.to_string());
+}
+
+#[test]
+fn empty_prep_block_is_dropped() {
+    let mut builder = Builder::new(Config::default());
+    let q = builder.qubit_allocate();
+    builder.begin_prep_block();
+    builder.end_prep_block();
+    builder.x(q);
+
+    let circuit = builder.finish(&Value::unit());
+    expect![[r"
+        q_0    ── X ──
+    "]]
+    .assert_eq(&circuit.to_string());
+}
+
+#[test]
+fn base_profile_measurement_is_rendered_once() {
+    let mut builder = Builder::new(Config {
+        base_profile: true,
+        ..Default::default()
+    });
+    let q = builder.qubit_allocate();
+    builder.h(q);
+    builder.m(q);
+
+    let num_measurements = |circuit: &Circuit| {
+        circuit
+            .operations
+            .iter()
+            .filter(|op| op.gate == "Measure")
+            .count()
+    };
+    assert_eq!(num_measurements(&builder.snapshot()), 1);
+    assert_eq!(num_measurements(&builder.snapshot()), 1);
+    assert_eq!(num_measurements(&builder.finish(&Value::unit())), 1);
+}
+
+#[test]
+fn classically_controlled_gate_references_stored_measurement() {
+    let mut builder = Builder::new(Config::default());
+    let q0 = builder.qubit_allocate();
+    let q1 = builder.qubit_allocate();
+    builder.h(q0);
+    let r = builder.m(q0);
+    builder.set_classical("r", r);
+    builder.begin_classical_control("r");
+    builder.x(q1);
+    builder.end_classical_control();
+
+    let circuit = builder.finish(&Value::unit());
+    let controlled = circuit
+        .operations
+        .last()
+        .expect("circuit should not be empty");
+    assert_eq!(controlled.controls, vec![Register::classical(0, 0)]);
+    expect![[r#"
+        q_0    ── H ──── M ─────────
+                         ╘═════ ● ══
+        q_1    ──────────────── X ──
+    "#]]
+    .assert_eq(&circuit.to_string());
+}
+
+#[test]
+fn classical_variable_bound_to_unknown_result_is_forgotten() {
+    let mut builder = Builder::new(Config::default());
+    let q = builder.qubit_allocate();
+    let r = builder.m(q);
+    builder.set_classical("r", r);
+    assert!(builder.has_classical_var("r"));
+
+    builder.set_classical("r", r + 1);
+    assert!(!builder.has_classical_var("r"));
+}
+
+#[test]
+fn reused_qubit_counts_once_towards_peak_qubits() {
+    let mut builder = Builder::new(Config {
+        base_profile: true,
+        ..Default::default()
+    });
+    let q = builder.qubit_allocate();
+    builder.h(q);
+    builder.mresetz(q);
+    builder.qubit_release(q);
+    let q = builder.qubit_allocate();
+    builder.x(q);
+    builder.qubit_release(q);
+
+    let circuit = builder.finish(&Value::unit());
+    assert_eq!(circuit.qubits.len(), 2);
+    assert_eq!(circuit.peak_qubits(), 1);
+}
+
+#[test]
+fn classical_wire_carries_measurement_results() {
+    let mut builder = Builder::new(Config {
+        show_classical_wires: true,
+        ..Default::default()
+    });
+    let q0 = builder.qubit_allocate();
+    let q1 = builder.qubit_allocate();
+    let q2 = builder.qubit_allocate();
+    builder.h(q0);
+    builder.m(q0);
+    let r = builder.m(q1);
+    builder.set_classical("r", r);
+    builder.begin_classical_control("r");
+    builder.x(q2);
+    builder.end_classical_control();
+
+    let circuit = builder.finish(&Value::unit());
+    assert_eq!(
+        circuit.classical_wires[0].results,
+        vec![Register::classical(0, 0), Register::classical(1, 0)]
+    );
+    expect![[r#"
+        q_0    ── H ──── M ────────────────
+        q_1    ──────────┼───── M ─────────
+        q_2    ──────────┼──────┼───── X ──
+        c_0    ══════════╧══════╧═════ ● ══
+    "#]]
+    .assert_eq(&circuit.to_string());
+}
+
+#[test]
+fn symbolic_rotation_renders_angle_expression() {
+    let mut builder = Builder::new(Config::default());
+    let q = builder.qubit_allocate();
+    builder.symbolic_rotation("rz", "theta", [q]);
+    builder.rz(0.5, q);
+
+    let circuit = builder.finish(&Value::unit());
+    expect![[r"
+        q_0     rz(theta)  rz(0.5000)
+    "]]
+    .assert_eq(&circuit.to_string());
+}
+
+#[test]
+fn note_attached_to_gate_survives_snapshot() {
+    let mut builder = Builder::new(Config::default());
+    let q = builder.qubit_allocate();
+    builder.h(q);
+    builder.annotate_last("error-corrected");
+    builder.x(q);
+
+    let circuit = builder.snapshot();
+    assert_eq!(
+        circuit.operations[0].note.as_deref(),
+        Some("error-corrected")
+    );
+    assert_eq!(circuit.operations[1].note, None);
+    let json = serde_json::to_string(&circuit.operations).expect("serialization should succeed");
+    expect![[r#"[{"gate":"H","targets":[{"qId":0,"type":0}],"note":"error-corrected"},{"gate":"X","targets":[{"qId":0,"type":0}]}]"#]]
+    .assert_eq(&json);
+}
+
+#[test]
+fn configured_measurement_symbol_names_measurements() {
+    let mut builder = Builder::new(Config {
+        measurement_symbol: "M",
+        ..Default::default()
+    });
+    let q = builder.qubit_allocate();
+    builder.mresetz(q);
+
+    let circuit = builder.finish(&Value::unit());
+    expect![[r#"
+        [
+            "M",
+            "|0〉",
+        ]
+    "#]]
+    .assert_debug_eq(
+        &circuit
+            .operations
+            .iter()
+            .map(|op| op.gate.as_str())
+            .collect::<Vec<_>>(),
+    );
+}
+
+#[test]
+fn configured_measurement_symbol_applies_to_deferred_measurements() {
+    let mut builder = Builder::new(Config {
+        base_profile: true,
+        measurement_symbol: "M",
+        ..Default::default()
+    });
+    let q = builder.qubit_allocate();
+    builder.m(q);
+
+    let circuit = builder.finish(&Value::unit());
+    assert_eq!(circuit.operations.len(), 1);
+    assert_eq!(circuit.operations[0].gate, "M");
+}
+
+#[test]
+fn library_operation_collapsed_into_single_spanning_operation() {
+    let mut builder = Builder::new(Config {
+        collapse_library_ops: true,
+        ..Default::default()
+    });
+    let q0 = builder.qubit_allocate();
+    let q1 = builder.qubit_allocate();
+    builder.enter_region("Main", false);
+    builder.enter_region("ApplyQFT", true);
+    builder.h(q1);
+    builder.enter_region("Controlled R1", false);
+    builder.cx(q0, q1);
+    builder.exit_region();
+    builder.h(q0);
+    builder.exit_region();
+    builder.x(q0);
+    builder.exit_region();
+
+    let circuit = builder.finish(&Value::unit());
+    assert_eq!(circuit.operations.len(), 2);
+    let qft = &circuit.operations[0];
+    assert_eq!(qft.gate, "ApplyQFT");
+    assert_eq!(qft.region.as_deref(), Some("Main"));
+    assert_eq!(
+        qft.targets,
+        vec![Register::quantum(q0), Register::quantum(q1)]
+    );
+    expect![[r#"
+        [
+            "H",
+            "X",
+            "H",
+        ]
+    "#]]
+    .assert_debug_eq(
+        &qft.children
+            .iter()
+            .map(|op| op.gate.as_str())
+            .collect::<Vec<_>>(),
+    );
+}
+
+#[test]
+fn library_operation_not_collapsed_when_disabled_or_single_gate() {
+    let mut builder = Builder::new(Config::default());
+    let q0 = builder.qubit_allocate();
+    let q1 = builder.qubit_allocate();
+    builder.enter_region("ApplyQFT", true);
+    builder.h(q1);
+    builder.cx(q0, q1);
+    builder.exit_region();
+    let circuit = builder.finish(&Value::unit());
+    assert_eq!(circuit.operations.len(), 2);
+
+    let mut builder = Builder::new(Config {
+        collapse_library_ops: true,
+        ..Default::default()
+    });
+    let q = builder.qubit_allocate();
+    builder.enter_region("H", true);
+    builder.h(q);
+    builder.exit_region();
+    let circuit = builder.finish(&Value::unit());
+    assert_eq!(circuit.operations.len(), 1);
+    assert_eq!(circuit.operations[0].gate, "H");
+    assert!(circuit.operations[0].children.is_empty());
+}
+
+#[test]
+fn gate_styles_are_attached_to_matching_gates() {
+    let mut builder = Builder::new(Config {
+        gate_styles: &[("X", 1, "cnot")],
+        ..Default::default()
+    });
+    let q0 = builder.qubit_allocate();
+    let q1 = builder.qubit_allocate();
+    let q2 = builder.qubit_allocate();
+    builder.x(q0);
+    builder.cx(q0, q1);
+    builder.cx(q1, q2);
+    builder.ccx(q0, q1, q2);
+    let circuit = builder.finish(&Value::unit());
+
+    assert_eq!(circuit.operations[0].style, None);
+    for op in &circuit.operations[1..3] {
+        assert_eq!(op.gate, "X");
+        let json = serde_json::to_string(op).expect("operation should serialize");
+        assert!(json.contains(r#""style":"cnot""#));
+    }
+    assert_eq!(circuit.operations[3].style, None);
+}
+
+#[test]
+fn deferred_measurements_get_style_and_region() {
+    let mut builder = Builder::new(Config {
+        base_profile: true,
+        gate_styles: &[("Measure", 0, "readout")],
+        ..Default::default()
+    });
+    let q = builder.qubit_allocate();
+    builder.enter_region("Main", false);
+    builder.h(q);
+    builder.m(q);
+    builder.exit_region();
+    let circuit = builder.finish(&Value::unit());
+
+    let measurement = circuit
+        .operations
+        .last()
+        .expect("circuit should not be empty");
+    assert!(measurement.is_measurement);
+    assert_eq!(measurement.region.as_deref(), Some("Main"));
+    assert_eq!(measurement.style.as_deref(), Some("readout"));
+}
@@ -4,7 +4,8 @@
 #[cfg(test)]
 mod tests;
 
-use rustc_hash::FxHashMap;
+use qsc_data_structures::index_map::IndexMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use serde::Serialize;
 use std::{fmt::Display, fmt::Write, ops::Not, vec};
 
@@ -14,6 +15,13 @@ use std::{fmt::Display, fmt::Write, ops::Not, vec};
 pub struct Circuit {
     pub operations: Vec<Operation>,
     pub qubits: Vec<Qubit>,
+    /// The maximum number of qubits allocated at the same time, when qubit allocations were tracked.
+    #[serde(skip)]
+    pub peak_live_qubits: Option<usize>,
+    /// Classical wires that carry measurement results separately from the qubit wires.
+    #[serde(rename = "classicalWires")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub classical_wires: Vec<ClassicalWire>,
 }
 
 #[derive(Clone, Serialize, Debug, PartialEq)]
@@ -37,11 +45,882 @@ pub struct Operation {
     pub targets: Vec<Register>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<Operation>,
+    /// A note attached to the operation (e.g. "error-corrected" or "physical"), rendered as a tooltip.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// The labels of the nested source regions the operation was emitted in (e.g. the names of the enclosing
+    /// operations), from outermost to innermost and separated by `/`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    /// A styling hint for viewers (e.g. a CSS class or a color name), used to color-code families of gates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+}
+
+impl Circuit {
+    /// Tallies the gates of the circuit that are relevant for resource estimation.
+    #[must_use]
+    pub fn resource_counts(&self) -> ResourceCounts {
+        let mut counts = ResourceCounts::default();
+        counts.add_operations(&self.operations);
+        counts
+    }
+
+    /// Combines consecutive rotations about the same axis on the same wire into a single rotation.
+    /// Rotations whose angle cannot be parsed from their display arguments are not merged.
+    #[must_use]
+    pub fn merge_adjacent_rotations(&self) -> Circuit {
+        let mut operations: Vec<Operation> = Vec::with_capacity(self.operations.len());
+
+        // Maintain a mapping from qubit to the last operation that acts on it
+        let mut last_op_by_qubit: FxHashMap<usize, usize> = FxHashMap::default();
+
+        for op in &self.operations {
+            if let [target] = op.targets.as_slice() {
+                if let Some(&last) = last_op_by_qubit.get(&target.q_id) {
+                    if let Some(merged) = merge_rotations(&operations[last], op) {
+                        operations[last] = merged;
+                        continue;
+                    }
+                }
+            }
+
+            for register in op.controls.iter().chain(&op.targets) {
+                last_op_by_qubit.insert(register.q_id, operations.len());
+            }
+            operations.push(op.clone());
+        }
+
+        Circuit {
+            operations,
+            qubits: self.qubits.clone(),
+            peak_live_qubits: self.peak_live_qubits,
+            classical_wires: self.classical_wires.clone(),
+        }
+    }
+
+    /// Replaces every Toffoli (`CX` with two qubit controls) with the standard decomposition into six CNOTs,
+    /// seven `T`/`T†` gates and two `H` gates on the same wires.
+    #[must_use]
+    pub fn decompose_ccx(&self) -> Circuit {
+        Circuit {
+            operations: decompose_ccx_operations(&self.operations),
+            qubits: self.qubits.clone(),
+            peak_live_qubits: self.peak_live_qubits,
+            classical_wires: self.classical_wires.clone(),
+        }
+    }
+
+    /// Removes the operations that only apply a global phase, which cannot be observed: `GPhase` gates and `Exp`
+    /// rotations about `PauliI` on every qubit. Those with quantum controls apply a relative phase to their controls
+    /// instead, so they are kept.
+    #[must_use]
+    pub fn drop_global_phase(&self) -> Circuit {
+        Circuit {
+            operations: drop_global_phase_operations(&self.operations),
+            qubits: self.qubits.clone(),
+            peak_live_qubits: self.peak_live_qubits,
+            classical_wires: self.classical_wires.clone(),
+        }
+    }
+
+    /// Reorders commuting operations, i.e. those that act on disjoint wires, into a deterministic order so
+    /// that circuits which only differ in the order of commuting operations canonicalize to the same circuit.
+    /// Each operation is placed in the earliest layer after every operation it shares a wire with, and the
+    /// operations within a layer are sorted by the lowest wire they act on.
+    #[must_use]
+    pub fn canonicalize(&self) -> Circuit {
+        let mut layer_by_qubit: FxHashMap<usize, usize> = FxHashMap::default();
+        let mut layered = Vec::with_capacity(self.operations.len());
+        for op in &self.operations {
+            let wires = op
+                .controls
+                .iter()
+                .chain(&op.targets)
+                .map(|register| register.q_id)
+                .collect::<Vec<_>>();
+            let layer = wires
+                .iter()
+                .filter_map(|q| layer_by_qubit.get(q).map(|layer| layer + 1))
+                .max()
+                .unwrap_or(0);
+            for &q in &wires {
+                layer_by_qubit.insert(q, layer);
+            }
+            let lowest_wire = wires.into_iter().min();
+            layered.push((layer, lowest_wire, op));
+        }
+        layered.sort_by_key(|&(layer, lowest_wire, _)| (layer, lowest_wire));
+
+        Circuit {
+            operations: layered.into_iter().map(|(_, _, op)| op.clone()).collect(),
+            qubits: self.qubits.clone(),
+            peak_live_qubits: self.peak_live_qubits,
+            classical_wires: self.classical_wires.clone(),
+        }
+    }
+
+    /// Re-lays out the circuit onto `num_qubits` hardware wires with linear-nearest-neighbor connectivity,
+    /// inserting SWAP gates so that every two-qubit gate acts on adjacent wires. Each qubit starts out on
+    /// the wire with the same index and its placement is updated as SWAPs are inserted.
+    #[must_use]
+    pub fn route_lnn(&self, num_qubits: usize) -> Circuit {
+        assert!(
+            self.qubits.iter().all(|q| q.id < num_qubits),
+            "circuit uses more qubits than there are hardware wires"
+        );
+
+        let mut router = LnnRouter::new(num_qubits);
+        for op in &self.operations {
+            router.route(op);
+        }
+        let classical_wires = self
+            .classical_wires
+            .iter()
+            .map(|wire| ClassicalWire {
+                id: wire.id,
+                results: wire
+                    .results
+                    .iter()
+                    .map(|r| router.remap_register(r))
+                    .collect(),
+            })
+            .collect();
+        Circuit {
+            peak_live_qubits: self.peak_live_qubits,
+            classical_wires,
+            ..router.into_circuit()
+        }
+    }
+
+    /// Returns the maximum number of qubits that are live at any moment, which is the true width of the circuit
+    /// for resource estimation. When qubit allocations were not tracked, every qubit is assumed to be live
+    /// throughout the circuit.
+    #[must_use]
+    pub fn peak_qubits(&self) -> usize {
+        self.peak_live_qubits.unwrap_or(self.qubits.len())
+    }
+
+    /// Returns the index of the first operation that measures a qubit, either directly or through one of the
+    /// operations it groups, or `None` if the circuit has no measurements.
+    #[must_use]
+    pub fn first_measurement(&self) -> Option<usize> {
+        self.operations.iter().position(is_measurement)
+    }
+
+    /// Returns the indices of the operations that were emitted within the source region with the given label,
+    /// directly or within a region nested in it, in circuit order.
+    #[must_use]
+    pub fn operations_in_region(&self, label: &str) -> Vec<usize> {
+        self.find_operations(|op| {
+            op.region
+                .as_deref()
+                .is_some_and(|region| region.split('/').any(|r| r == label))
+        })
+    }
+
+    /// Returns the indices of the operations that satisfy the given predicate, in circuit order.
+    /// Grouped operations are matched as a whole, without looking into the operations they contain.
+    #[must_use]
+    pub fn find_operations(&self, pred: impl Fn(&Operation) -> bool) -> Vec<usize> {
+        self.operations
+            .iter()
+            .enumerate()
+            .filter_map(|(index, op)| pred(op).then_some(index))
+            .collect()
+    }
+
+    /// Replaces every reset with a measurement followed by an `X` that is classically controlled on the
+    /// measurement result, which is how hardware without a native reset implements it. Each measurement
+    /// records a new result on the wire of the qubit being reset, and is named like the measurements already
+    /// in the circuit (see `Config::measurement_symbol`), or with the default symbol if there are none.
+    #[must_use]
+    pub fn expand_resets(&self) -> Circuit {
+        let measurement_symbol = find_measurement_symbol(&self.operations)
+            .unwrap_or(Config::default().measurement_symbol);
+        let mut results_on_qubit: FxHashMap<usize, usize> =
+            self.qubits.iter().map(|q| (q.id, q.num_children)).collect();
+        let operations =
+            expand_reset_operations(&self.operations, measurement_symbol, &mut results_on_qubit);
+        Circuit {
+            operations,
+            qubits: self
+                .qubits
+                .iter()
+                .map(|q| Qubit {
+                    id: q.id,
+                    num_children: results_on_qubit[&q.id],
+                })
+                .collect(),
+            peak_live_qubits: self.peak_live_qubits,
+            classical_wires: self.classical_wires.clone(),
+        }
+    }
+
+    /// Returns the number of leading operations that do not depend on any measurement result, i.e. the
+    /// index of the first classically-controlled operation, or the number of operations if there is none.
+    #[must_use]
+    pub fn static_prefix_len(&self) -> usize {
+        self.operations
+            .iter()
+            .position(is_measurement_dependent)
+            .unwrap_or(self.operations.len())
+    }
+
+    /// Rewrites the circuit by repeatedly replacing windows of consecutive operations that match one of the rules,
+    /// until no rule matches anymore. Rules are tried in order at each position, so earlier rules take precedence.
+    /// Every rule is expected to make the circuit simpler, otherwise rewriting might not terminate.
+    #[must_use]
+    pub fn apply_rewrite_rules(&self, rules: &[RewriteRule]) -> Circuit {
+        let mut operations = self.operations.clone();
+        loop {
+            let mut rewritten = Vec::with_capacity(operations.len());
+            let mut changed = false;
+            let mut index = 0;
+            while index < operations.len() {
+                let replacement = rules.iter().find_map(|rule| {
+                    operations
+                        .get(index..index + rule.window)
+                        .and_then(rule.rewrite)
+                        .map(|replacement| (rule.window, replacement))
+                });
+                if let Some((window, replacement)) = replacement {
+                    rewritten.extend(replacement);
+                    index += window;
+                    changed = true;
+                } else {
+                    rewritten.push(operations[index].clone());
+                    index += 1;
+                }
+            }
+            operations = rewritten;
+            if !changed {
+                break;
+            }
+        }
+
+        Circuit {
+            operations,
+            qubits: self.qubits.clone(),
+            peak_live_qubits: self.peak_live_qubits,
+            classical_wires: self.classical_wires.clone(),
+        }
+    }
+
+    /// Counts how many operations act on each qubit, either as a control or as a target, which helps
+    /// identify the hotspot qubits of the circuit. Qubits that no operation acts on have a count of zero.
+    /// Classical registers don't count, so a gate controlled on a measurement result doesn't act on the
+    /// measured qubit.
+    #[must_use]
+    pub fn per_qubit_gate_counts(&self) -> IndexMap<usize, usize> {
+        let mut counts = IndexMap::new();
+        for qubit in &self.qubits {
+            counts.insert(qubit.id, 0);
+        }
+        for op in &self.operations {
+            let qubits = op
+                .controls
+                .iter()
+                .chain(&op.targets)
+                .filter(|register| register.c_id.is_none())
+                .map(|register| register.q_id)
+                .collect::<FxHashSet<_>>();
+            for q in qubits {
+                match counts.get_mut(q) {
+                    Some(count) => *count += 1,
+                    None => counts.insert(q, 1),
+                }
+            }
+        }
+        counts
+    }
+
+    /// Checks whether the two circuits are identical once the qubits of one of them are relabeled, e.g. to compare
+    /// a circuit against its routed version. The relabeling is built up while walking both operation sequences
+    /// in lockstep, since each pair of registers in the same position forces where a qubit gets mapped to.
+    #[must_use]
+    pub fn equivalent_up_to_relabeling(&self, other: &Circuit) -> bool {
+        if self.qubits.len() != other.qubits.len() {
+            return false;
+        }
+
+        let mut relabeling = QubitRelabeling::default();
+        if !relabeling.match_operations(&self.operations, &other.operations) {
+            return false;
+        }
+
+        // Qubits that no operation acts on can be mapped to each other freely, but the ones that were mapped
+        // need to hold the same number of results.
+        let num_children = |qubits: &[Qubit], id: usize| {
+            qubits.iter().find(|q| q.id == id).map(|q| q.num_children)
+        };
+        relabeling.forward.iter().all(|(&q, &mapped)| {
+            num_children(&self.qubits, q) == num_children(&other.qubits, mapped)
+        })
+    }
+
+    /// Renders the circuit as a list of QIR-style comments, one line per gate, naming the QIR intrinsic
+    /// each gate corresponds to along with the qubit and result indices it acts on. Results are numbered
+    /// in the order the measurements appear in the circuit, the same way QIR allocates them.
+    /// Grouped operations are rendered through the gates they contain.
+    #[must_use]
+    pub fn to_qir_comments(&self) -> String {
+        let mut results = FxHashMap::default();
+        let mut out = String::new();
+        write_qir_comments(&self.operations, &mut results, &mut out);
+        out
+    }
+}
+
+fn write_qir_comments(
+    operations: &[Operation],
+    results: &mut FxHashMap<(usize, usize), usize>,
+    out: &mut String,
+) {
+    for op in operations {
+        if !op.children.is_empty() {
+            write_qir_comments(&op.children, results, out);
+            continue;
+        }
+
+        let mut result_index = |q_id: usize, c_id: usize| {
+            let next = results.len();
+            *results.entry((q_id, c_id)).or_insert(next)
+        };
+
+        // Classical controls condition the gate on measurement results rather than being arguments of the intrinsic.
+        let mut args = op.display_args.iter().cloned().collect::<Vec<_>>();
+        let mut conditions = Vec::new();
+        for register in &op.controls {
+            match register.c_id {
+                None => args.push(format!("qubit {}", register.q_id)),
+                Some(c_id) => {
+                    conditions.push(format!("result {}", result_index(register.q_id, c_id)));
+                }
+            }
+        }
+        for register in &op.targets {
+            match register.c_id {
+                None => args.push(format!("qubit {}", register.q_id)),
+                Some(c_id) => args.push(format!("result {}", result_index(register.q_id, c_id))),
+            }
+        }
+
+        let mut line = match qir_intrinsic_name(op) {
+            Some(name) => format!("; {name}({})", args.join(", ")),
+            None => format!(
+                "; {}({}): no matching QIR intrinsic",
+                op.gate,
+                args.join(", ")
+            ),
+        };
+        if !conditions.is_empty() {
+            write!(line, " conditioned on {}", conditions.join(", "))
+                .expect("writing to string should succeed");
+        }
+        writeln!(out, "{line}").expect("writing to string should succeed");
+    }
+}
+
+/// The name of the QIR intrinsic the operation corresponds to, if there is one.
+fn qir_intrinsic_name(op: &Operation) -> Option<&'static str> {
+    if op.is_measurement {
+        return Some("__quantum__qis__mz__body");
+    }
+    let num_controls = op
+        .controls
+        .iter()
+        .filter(|register| register.c_id.is_none())
+        .count();
+    let name = match (op.gate.as_str(), num_controls, op.is_adjoint) {
+        ("H", 0, false) => "__quantum__qis__h__body",
+        ("X", 0, false) => "__quantum__qis__x__body",
+        ("X", 1, false) => "__quantum__qis__cnot__body",
+        ("X" | "CX", 2, false) => "__quantum__qis__ccx__body",
+        ("Y", 0, false) => "__quantum__qis__y__body",
+        ("Y", 1, false) => "__quantum__qis__cy__body",
+        ("Z", 0, false) => "__quantum__qis__z__body",
+        ("Z", 1, false) => "__quantum__qis__cz__body",
+        ("S", 0, false) => "__quantum__qis__s__body",
+        ("S", 0, true) => "__quantum__qis__s__adj",
+        ("T", 0, false) => "__quantum__qis__t__body",
+        ("T", 0, true) => "__quantum__qis__t__adj",
+        ("rx", 0, false) => "__quantum__qis__rx__body",
+        ("ry", 0, false) => "__quantum__qis__ry__body",
+        ("rz", 0, false) => "__quantum__qis__rz__body",
+        ("rxx", 0, false) => "__quantum__qis__rxx__body",
+        ("ryy", 0, false) => "__quantum__qis__ryy__body",
+        ("rzz", 0, false) => "__quantum__qis__rzz__body",
+        ("SWAP", 0, false) => "__quantum__qis__swap__body",
+        (gate, 0, false) if gate == KET_ZERO => "__quantum__qis__reset__body",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// A one-to-one mapping between the qubits of two circuits.
+#[derive(Default)]
+struct QubitRelabeling {
+    forward: FxHashMap<usize, usize>,
+    backward: FxHashMap<usize, usize>,
+}
+
+impl QubitRelabeling {
+    fn match_operations(&mut self, ops: &[Operation], other_ops: &[Operation]) -> bool {
+        ops.len() == other_ops.len()
+            && ops
+                .iter()
+                .zip(other_ops)
+                .all(|(op, other_op)| self.match_operation(op, other_op))
+    }
+
+    fn match_operation(&mut self, op: &Operation, other_op: &Operation) -> bool {
+        op.gate == other_op.gate
+            && op.display_args == other_op.display_args
+            && op.is_controlled == other_op.is_controlled
+            && op.is_adjoint == other_op.is_adjoint
+            && op.is_measurement == other_op.is_measurement
+            && self.match_registers(&op.controls, &other_op.controls)
+            && self.match_registers(&op.targets, &other_op.targets)
+            && self.match_operations(&op.children, &other_op.children)
+    }
+
+    fn match_registers(&mut self, registers: &[Register], other_registers: &[Register]) -> bool {
+        registers.len() == other_registers.len()
+            && registers
+                .iter()
+                .zip(other_registers)
+                .all(|(register, other_register)| {
+                    register.r#type == other_register.r#type
+                        && register.c_id == other_register.c_id
+                        && self.map(register.q_id, other_register.q_id)
+                })
+    }
+
+    fn map(&mut self, q: usize, other_q: usize) -> bool {
+        match (self.forward.get(&q), self.backward.get(&other_q)) {
+            (Some(&mapped), Some(&mapped_back)) => mapped == other_q && mapped_back == q,
+            (None, None) => {
+                self.forward.insert(q, other_q);
+                self.backward.insert(other_q, q);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn decompose_ccx_operations(operations: &[Operation]) -> Vec<Operation> {
+    let mut decomposed = Vec::with_capacity(operations.len());
+    for op in operations {
+        match op.controls.as_slice() {
+            [ctl0, ctl1]
+                if op.gate == "CX"
+                    && op.is_controlled
+                    && !op.is_adjoint
+                    && ctl0.c_id.is_none()
+                    && ctl1.c_id.is_none() =>
+            {
+                let [target] = op.targets.as_slice() else {
+                    panic!("Toffoli should have a single target");
+                };
+                decomposed.extend(ccx_decomposition(ctl0, ctl1, target));
+            }
+            _ => decomposed.push(Operation {
+                children: decompose_ccx_operations(&op.children),
+                ..op.clone()
+            }),
+        }
+    }
+    decomposed
+}
+
+fn drop_global_phase_operations(operations: &[Operation]) -> Vec<Operation> {
+    operations
+        .iter()
+        .filter(|op| !is_global_phase(op))
+        .filter_map(|op| {
+            if op.children.is_empty() {
+                return Some(op.clone());
+            }
+            // A group made up of global phases only is dropped along with them.
+            let children = drop_global_phase_operations(&op.children);
+            (!children.is_empty()).then(|| Operation {
+                children,
+                ..op.clone()
+            })
+        })
+        .collect()
+}
+
+/// Whether the operation only applies a global phase, i.e. it is a `GPhase` gate or an `Exp` rotation about `PauliI`
+/// on every qubit, and it has no quantum controls.
+fn is_global_phase(op: &Operation) -> bool {
+    if op
+        .controls
+        .iter()
+        .any(|register| register.r#type == QUANTUM_REGISTER)
+    {
+        return false;
+    }
+
+    match op.gate.as_str() {
+        "GPhase" => op.targets.is_empty(),
+        // The Pauli bases are the first display argument, e.g. `[PauliI, PauliI], 0.5`.
+        "Exp" => op
+            .display_args
+            .as_deref()
+            .and_then(|args| args.strip_prefix('['))
+            .and_then(|args| args.split_once(']'))
+            .is_some_and(|(paulis, _)| paulis.split(',').all(|p| p.trim() == "PauliI")),
+        _ => false,
+    }
+}
+
+fn find_measurement_symbol(operations: &[Operation]) -> Option<&str> {
+    operations.iter().find_map(|op| {
+        if op.is_measurement {
+            Some(op.gate.as_str())
+        } else {
+            find_measurement_symbol(&op.children)
+        }
+    })
+}
+
+fn expand_reset_operations(
+    operations: &[Operation],
+    measurement_symbol: &str,
+    results_on_qubit: &mut FxHashMap<usize, usize>,
+) -> Vec<Operation> {
+    let mut expanded = Vec::with_capacity(operations.len());
+    for op in operations {
+        match op.targets.as_slice() {
+            [target]
+                if op.gate == KET_ZERO
+                    && !op.is_controlled
+                    && target.c_id.is_none()
+                    && op.children.is_empty() =>
+            {
+                let num_results = results_on_qubit.entry(target.q_id).or_default();
+                let result = Register::classical(target.q_id, *num_results);
+                *num_results += 1;
+                expanded.push(Operation {
+                    gate: measurement_symbol.into(),
+                    display_args: None,
+                    is_controlled: false,
+                    is_adjoint: false,
+                    is_measurement: true,
+                    controls: vec![target.clone()],
+                    targets: vec![result.clone()],
+                    children: vec![],
+                    note: None,
+                    region: None,
+                    style: None,
+                });
+                expanded.push(Operation {
+                    gate: "X".into(),
+                    display_args: None,
+                    is_controlled: true,
+                    is_adjoint: false,
+                    is_measurement: false,
+                    controls: vec![result],
+                    targets: vec![target.clone()],
+                    children: vec![],
+                    note: None,
+                    region: None,
+                    style: None,
+                });
+            }
+            _ => expanded.push(Operation {
+                children: expand_reset_operations(
+                    &op.children,
+                    measurement_symbol,
+                    results_on_qubit,
+                ),
+                ..op.clone()
+            }),
+        }
+    }
+    expanded
+}
+
+/// The Toffoli decomposition from Nielsen & Chuang, Figure 4.9.
+fn ccx_decomposition(ctl0: &Register, ctl1: &Register, target: &Register) -> Vec<Operation> {
+    let gate = |name: &str, is_adjoint: bool, target: &Register| Operation {
+        gate: name.into(),
+        display_args: None,
+        is_controlled: false,
+        is_adjoint,
+        is_measurement: false,
+        controls: vec![],
+        targets: vec![target.clone()],
+        children: vec![],
+        note: None,
+        region: None,
+        style: None,
+    };
+    let cx = |control: &Register, target: &Register| Operation {
+        is_controlled: true,
+        controls: vec![control.clone()],
+        ..gate("X", false, target)
+    };
+    let t = |target: &Register| gate("T", false, target);
+    let t_adj = |target: &Register| gate("T", true, target);
+    let h = |target: &Register| gate("H", false, target);
+
+    vec![
+        h(target),
+        cx(ctl1, target),
+        t_adj(target),
+        cx(ctl0, target),
+        t(target),
+        cx(ctl1, target),
+        t_adj(target),
+        cx(ctl0, target),
+        t(ctl1),
+        t(target),
+        h(target),
+        cx(ctl0, ctl1),
+        t(ctl0),
+        t_adj(ctl1),
+        cx(ctl0, ctl1),
+    ]
+}
+
+/// Whether the operation, or any of the operations it groups, is a measurement.
+fn is_measurement(op: &Operation) -> bool {
+    op.is_measurement || op.children.iter().any(is_measurement)
+}
+
+/// Whether the operation, or any of the operations it groups, is controlled on a classical register.
+fn is_measurement_dependent(op: &Operation) -> bool {
+    op.controls
+        .iter()
+        .any(|register| register.r#type == CLASSICAL_REGISTER)
+        || op.children.iter().any(is_measurement_dependent)
+}
+
+/// Keeps track of where each qubit is placed while routing a circuit onto a linear-nearest-neighbor topology.
+struct LnnRouter {
+    /// The wire each qubit is currently placed on
+    wire_of_qubit: Vec<usize>,
+    /// The qubit currently placed on each wire
+    qubit_on_wire: Vec<usize>,
+    /// Maps the classical registers of the original circuit to the routed circuit
+    results: FxHashMap<(usize, usize), (usize, usize)>,
+    /// The number of measurement results on each wire
+    results_on_wire: Vec<usize>,
+    operations: Vec<Operation>,
+}
+
+impl LnnRouter {
+    fn new(num_qubits: usize) -> Self {
+        Self {
+            wire_of_qubit: (0..num_qubits).collect(),
+            qubit_on_wire: (0..num_qubits).collect(),
+            results: FxHashMap::default(),
+            results_on_wire: vec![0; num_qubits],
+            operations: vec![],
+        }
+    }
+
+    fn route(&mut self, op: &Operation) {
+        let mut qubits = op
+            .controls
+            .iter()
+            .chain(&op.targets)
+            .filter(|r| r.c_id.is_none())
+            .map(|r| r.q_id)
+            .collect::<Vec<_>>();
+        qubits.dedup();
+
+        // Only two-qubit gates are routed, any other gate is just placed on the current wires
+        if let [q0, q1] = qubits[..] {
+            self.bring_adjacent(q0, q1);
+        }
+
+        let op = self.remap(op);
+        self.operations.push(op);
+    }
+
+    fn bring_adjacent(&mut self, q0: usize, q1: usize) {
+        loop {
+            let (w0, w1) = (self.wire_of_qubit[q0], self.wire_of_qubit[q1]);
+            if w0.abs_diff(w1) <= 1 {
+                break;
+            }
+            let next = if w0 < w1 { w0 + 1 } else { w0 - 1 };
+            self.swap(w0, next);
+        }
+    }
+
+    fn swap(&mut self, w0: usize, w1: usize) {
+        self.operations.push(Operation {
+            gate: "SWAP".into(),
+            display_args: None,
+            is_controlled: false,
+            is_adjoint: false,
+            is_measurement: false,
+            controls: vec![],
+            targets: vec![Register::quantum(w0), Register::quantum(w1)],
+            children: vec![],
+            note: None,
+            region: None,
+            style: None,
+        });
+
+        let (q0, q1) = (self.qubit_on_wire[w0], self.qubit_on_wire[w1]);
+        self.qubit_on_wire.swap(w0, w1);
+        self.wire_of_qubit[q0] = w1;
+        self.wire_of_qubit[q1] = w0;
+    }
+
+    fn remap(&mut self, op: &Operation) -> Operation {
+        Operation {
+            controls: op.controls.iter().map(|r| self.remap_register(r)).collect(),
+            targets: op.targets.iter().map(|r| self.remap_register(r)).collect(),
+            children: op.children.iter().map(|child| self.remap(child)).collect(),
+            ..op.clone()
+        }
+    }
+
+    fn remap_register(&mut self, register: &Register) -> Register {
+        match register.c_id {
+            None => Register::quantum(self.wire_of_qubit[register.q_id]),
+            Some(c_id) => {
+                let key = (register.q_id, c_id);
+                let (wire, c_id) = if let Some(result) = self.results.get(&key) {
+                    *result
+                } else {
+                    // A result is placed on the wire of the qubit at the time it is measured
+                    let wire = self.wire_of_qubit[register.q_id];
+                    let result = (wire, self.results_on_wire[wire]);
+                    self.results_on_wire[wire] += 1;
+                    self.results.insert(key, result);
+                    result
+                };
+                Register::classical(wire, c_id)
+            }
+        }
+    }
+
+    fn into_circuit(self) -> Circuit {
+        Circuit {
+            operations: self.operations,
+            qubits: self
+                .results_on_wire
+                .iter()
+                .enumerate()
+                .map(|(id, num_children)| Qubit {
+                    id,
+                    num_children: *num_children,
+                })
+                .collect(),
+            peak_live_qubits: None,
+            classical_wires: vec![],
+        }
+    }
+}
+
+fn merge_rotations(first: &Operation, second: &Operation) -> Option<Operation> {
+    let is_rotation = |op: &Operation| {
+        matches!(op.gate.as_str(), "rx" | "ry" | "rz")
+            && !op.is_controlled
+            && !op.is_adjoint
+            && op.children.is_empty()
+    };
+    // Both rotations need to be plain rotations on the same wires, otherwise merging would drop the controls or
+    // the classical condition of the second one.
+    if !is_rotation(first)
+        || !is_rotation(second)
+        || first.gate != second.gate
+        || first.targets != second.targets
+        || first.controls != second.controls
+    {
+        return None;
+    }
+
+    let angle = |op: &Operation| op.display_args.as_ref()?.parse::<f64>().ok();
+    let theta = angle(first)? + angle(second)?;
+    Some(Operation {
+        display_args: Some(format!("{theta:.4}")),
+        ..first.clone()
+    })
+}
+
+/// A rule used to rewrite a circuit, which replaces a window of consecutive operations.
+#[derive(Clone, Copy)]
+pub struct RewriteRule {
+    /// The number of consecutive operations the rule matches.
+    pub window: usize,
+    /// Gets the operations that replace the window, or `None` if the rule does not match it.
+    pub rewrite: fn(&[Operation]) -> Option<Vec<Operation>>,
+}
+
+impl RewriteRule {
+    /// Removes two consecutive applications of the same self-inverse gate (`H`, `X`, `Y`, `Z` and their
+    /// controlled variants) on the same wires, since together they amount to the identity.
+    #[must_use]
+    pub fn cancel_self_inverse_pairs() -> Self {
+        Self {
+            window: 2,
+            rewrite: |ops| match ops {
+                [first, second]
+                    if matches!(first.gate.as_str(), "H" | "X" | "Y" | "Z")
+                        && first == second
+                        && !first.is_measurement
+                        && first.children.is_empty()
+                        && first.controls.iter().all(|c| c.c_id.is_none()) =>
+                {
+                    Some(Vec::new())
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Gate counts derived from a circuit.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ResourceCounts {
+    /// The number of `T` and `Adjoint T` gates.
+    pub t_count: usize,
+    /// The number of singly-controlled `X` gates.
+    pub cnot_count: usize,
+    /// The number of single-qubit rotation gates.
+    pub rotation_count: usize,
+}
+
+impl ResourceCounts {
+    fn add_operations(&mut self, operations: &[Operation]) {
+        for op in operations {
+            // Grouped operations are counted through the gates they contain.
+            if !op.children.is_empty() {
+                self.add_operations(&op.children);
+                continue;
+            }
+
+            match op.gate.as_str() {
+                "T" if !op.is_controlled => self.t_count += 1,
+                "X" if op.is_controlled
+                    && matches!(op.controls.as_slice(), [control] if control.c_id.is_none()) =>
+                {
+                    self.cnot_count += 1;
+                }
+                "rx" | "ry" | "rz" if !op.is_controlled => self.rotation_count += 1,
+                _ => {}
+            }
+        }
+    }
 }
 
 const QUANTUM_REGISTER: usize = 0;
 const CLASSICAL_REGISTER: usize = 1;
 
+/// The gate a qubit reset is rendered as.
+#[allow(clippy::unicode_not_nfc)]
+pub(crate) static KET_ZERO: &str = "|0〉";
+
 #[derive(Serialize, Debug, Eq, Hash, PartialEq, Clone)]
 pub struct Register {
     #[serde(rename = "qId")]
@@ -77,10 +956,42 @@ pub struct Qubit {
     pub num_children: usize,
 }
 
-#[derive(Clone, Debug, Copy, Default)]
+/// A classical wire drawn below the qubit wires, carrying the given measurement results.
+#[derive(PartialEq, Clone, Serialize, Debug)]
+pub struct ClassicalWire {
+    pub id: usize,
+    pub results: Vec<Register>,
+}
+
+#[derive(Clone, Debug, Copy)]
 pub struct Config {
     /// Perform Base Profile decompositions
     pub base_profile: bool,
+    /// Render measurement results on a dedicated classical wire
+    pub show_classical_wires: bool,
+    /// Gate name used for measurement operations
+    pub measurement_symbol: &'static str,
+    /// Render each call to a library operation that emits several gates as a single box
+    /// spanning the qubits it acts on
+    pub collapse_library_ops: bool,
+    /// Styling hints attached to the matching operations, as triples of gate name, number of quantum controls and
+    /// style (e.g. `("X", 1, "cnot")` styles CNOT gates but not X or Toffoli gates)
+    pub gate_styles: &'static [(&'static str, usize, &'static str)],
+    /// Remove the gates that only apply a global phase, keeping the controlled ones
+    pub drop_global_phase: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            base_profile: false,
+            show_classical_wires: false,
+            measurement_symbol: "Measure",
+            collapse_library_ops: false,
+            gate_styles: &[],
+            drop_global_phase: false,
+        }
+    }
 }
 
 type ObjectsByColumn = FxHashMap<usize, String>;
@@ -94,6 +1005,7 @@ struct Row {
 enum Wire {
     Qubit { q_id: usize },
     Classical { start_column: Option<usize> },
+    Track { id: usize },
 }
 
 impl Row {
@@ -102,7 +1014,7 @@ impl Row {
             Wire::Qubit { .. } => {
                 self.add(column, fmt_on_qubit_wire(object));
             }
-            Wire::Classical { .. } => {
+            Wire::Classical { .. } | Wire::Track { .. } => {
                 self.add(column, fmt_on_classical_wire(object));
             }
         };
@@ -124,6 +1036,7 @@ impl Row {
         if self.objects.get(&column).is_none() {
             match self.wire {
                 Wire::Qubit { .. } => self.add(column, QUBIT_WIRE_CROSS),
+                Wire::Track { .. } => self.add(column, CLASSICAL_WIRE_CROSS),
                 Wire::Classical { start_column } => {
                     if start_column.is_some() {
                         self.add(column, CLASSICAL_WIRE_CROSS);
@@ -139,6 +1052,7 @@ impl Row {
         if self.objects.get(&column).is_none() {
             match self.wire {
                 Wire::Qubit { .. } => self.add(column, QUBIT_WIRE_DASHED_CROSS),
+                Wire::Track { .. } => self.add(column, CLASSICAL_WIRE_DASHED_CROSS),
                 Wire::Classical { start_column } => {
                     if start_column.is_some() {
                         self.add(column, CLASSICAL_WIRE_DASHED_CROSS);
@@ -157,6 +1071,13 @@ impl Row {
         }
     }
 
+    fn add_result(&mut self, column: usize) {
+        match self.wire {
+            Wire::Track { .. } => self.add(column, CLASSICAL_WIRE_RESULT),
+            _ => self.start_classical(column),
+        }
+    }
+
     fn add(&mut self, column: usize, v: impl Into<String>) {
         self.objects.insert(column, v.into());
         self.next_column = column + 1;
@@ -189,6 +1110,17 @@ impl Row {
                     }
                 }
             }
+            Wire::Track { id } => {
+                s.write_str(&fmt_classical_label(*id))?;
+                for column in 1..end_column {
+                    let val = self.objects.get(&column);
+                    if let Some(v) = val {
+                        s.write_str(v)?;
+                    } else {
+                        s.write_str(CLASSICAL_WIRE)?;
+                    }
+                }
+            }
         }
         writeln!(f, "{}", s.trim_end())?;
         Ok(())
@@ -201,6 +1133,7 @@ const CLASSICAL_WIRE: &str = "═══════";
 const QUBIT_WIRE_CROSS: &str = "───┼───";
 const CLASSICAL_WIRE_CROSS: &str = "═══╪═══";
 const CLASSICAL_WIRE_START: &str = "   ╘═══";
+const CLASSICAL_WIRE_RESULT: &str = "═══╧═══";
 const QUBIT_WIRE_DASHED_CROSS: &str = "───┆───";
 const CLASSICAL_WIRE_DASHED_CROSS: &str = "═══┆═══";
 const VERTICAL_DASHED: &str = "   ┆   ";
@@ -214,6 +1147,13 @@ fn fmt_qubit_label(id: usize) -> String {
     format!("q_{id: <rest$}")
 }
 
+/// "c_0  "
+#[allow(clippy::doc_markdown)]
+fn fmt_classical_label(id: usize) -> String {
+    let rest = COLUMN_WIDTH - 2;
+    format!("c_{id: <rest$}")
+}
+
 /// "── A ──"
 fn fmt_on_qubit_wire(obj: &str) -> String {
     format!("{:─^COLUMN_WIDTH$}", format!(" {obj} "))
@@ -232,6 +1172,14 @@ impl Display for Circuit {
         // to row in the diagram
         let mut register_to_row = FxHashMap::default();
 
+        // Results carried by a classical wire are drawn on that wire
+        // rather than on a wire of their own under the measured qubit
+        let results_on_wires = self
+            .classical_wires
+            .iter()
+            .flat_map(|wire| wire.results.iter().map(|r| (r.q_id, r.c_id)))
+            .collect::<FxHashSet<_>>();
+
         // Initialize all qubit and classical wires
         for q in &self.qubits {
             rows.push(Row {
@@ -243,6 +1191,9 @@ impl Display for Circuit {
             register_to_row.insert((q.id, None), rows.len() - 1);
 
             for i in 0..q.num_children {
+                if results_on_wires.contains(&(q.id, Some(i))) {
+                    continue;
+                }
                 rows.push(Row {
                     wire: Wire::Classical { start_column: None },
                     objects: FxHashMap::default(),
@@ -253,6 +1204,18 @@ impl Display for Circuit {
             }
         }
 
+        for wire in &self.classical_wires {
+            rows.push(Row {
+                wire: Wire::Track { id: wire.id },
+                objects: FxHashMap::default(),
+                next_column: 1,
+            });
+
+            for r in &wire.results {
+                register_to_row.insert((r.q_id, r.c_id), rows.len() - 1);
+            }
+        }
+
         for o in &self.operations {
             // Row indexes for the targets for this operation
             let targets = o
@@ -296,8 +1259,10 @@ impl Display for Circuit {
             // Add the operation to the diagram
             for i in targets {
                 let row = &mut rows[i];
-                if matches!(row.wire, Wire::Classical { .. }) && o.is_measurement {
-                    row.start_classical(column);
+                if matches!(row.wire, Wire::Classical { .. } | Wire::Track { .. })
+                    && o.is_measurement
+                {
+                    row.add_result(column);
                 } else {
                     row.add_gate(column, &o.gate, o.display_args.as_deref(), o.is_adjoint);
                 };
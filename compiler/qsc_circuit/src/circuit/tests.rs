@@ -4,11 +4,31 @@
 use super::*;
 use expect_test::expect;
 
+/// Builds an operation applying the gate to the registers, which is a measurement when the gate is `Measure`.
+fn gate(name: &str, controls: Vec<Register>, targets: Vec<Register>) -> Operation {
+    let is_measurement = name == "Measure";
+    Operation {
+        gate: name.to_string(),
+        display_args: None,
+        is_controlled: !controls.is_empty() && !is_measurement,
+        is_adjoint: false,
+        is_measurement,
+        controls,
+        targets,
+        children: vec![],
+        note: None,
+        region: None,
+        style: None,
+    }
+}
+
 #[test]
 fn empty() {
     let c = Circuit {
         operations: vec![],
         qubits: vec![],
+        peak_live_qubits: None,
+        classical_wires: vec![],
     };
 
     expect![[""]].assert_eq(&c.to_string());
@@ -28,6 +48,8 @@ fn no_gates() {
                 num_children: 0,
             },
         ],
+        peak_live_qubits: None,
+        classical_wires: vec![],
     };
 
     expect![[r"
@@ -50,6 +72,9 @@ fn bell() {
                 controls: vec![],
                 targets: vec![Register::quantum(0)],
                 children: vec![],
+                note: None,
+                region: None,
+                style: None,
             },
             Operation {
                 gate: "X".to_string(),
@@ -60,6 +85,9 @@ fn bell() {
                 controls: vec![Register::quantum(0)],
                 targets: vec![Register::quantum(1)],
                 children: vec![],
+                note: None,
+                region: None,
+                style: None,
             },
             Operation {
                 gate: "Measure".to_string(),
@@ -70,6 +98,9 @@ fn bell() {
                 controls: vec![Register::quantum(0)],
                 targets: vec![Register::classical(0, 0)],
                 children: vec![],
+                note: None,
+                region: None,
+                style: None,
             },
             Operation {
                 gate: "Measure".to_string(),
@@ -80,6 +111,9 @@ fn bell() {
                 controls: vec![Register::quantum(1)],
                 targets: vec![Register::classical(1, 0)],
                 children: vec![],
+                note: None,
+                region: None,
+                style: None,
             },
         ],
         qubits: vec![
@@ -92,6 +126,8 @@ fn bell() {
                 num_children: 1,
             },
         ],
+        peak_live_qubits: None,
+        classical_wires: vec![],
     };
 
     expect![[r"
@@ -116,6 +152,9 @@ fn control_classical() {
                 controls: vec![Register::quantum(0)],
                 targets: vec![Register::classical(0, 0)],
                 children: vec![],
+                note: None,
+                region: None,
+                style: None,
             },
             Operation {
                 gate: "X".to_string(),
@@ -126,6 +165,9 @@ fn control_classical() {
                 controls: vec![Register::classical(0, 0)],
                 targets: vec![Register::quantum(2)],
                 children: vec![],
+                note: None,
+                region: None,
+                style: None,
             },
             Operation {
                 gate: "X".to_string(),
@@ -136,6 +178,9 @@ fn control_classical() {
                 controls: vec![Register::quantum(0)],
                 targets: vec![Register::quantum(2)],
                 children: vec![],
+                note: None,
+                region: None,
+                style: None,
             },
         ],
         qubits: vec![
@@ -152,6 +197,8 @@ fn control_classical() {
                 num_children: 0,
             },
         ],
+        peak_live_qubits: None,
+        classical_wires: vec![],
     };
 
     expect![[r"
@@ -176,6 +223,9 @@ fn two_measurements() {
                 controls: vec![Register::quantum(0)],
                 targets: vec![Register::classical(0, 0)],
                 children: vec![],
+                note: None,
+                region: None,
+                style: None,
             },
             Operation {
                 gate: "Measure".to_string(),
@@ -186,12 +236,17 @@ fn two_measurements() {
                 controls: vec![Register::quantum(0)],
                 targets: vec![Register::classical(0, 1)],
                 children: vec![],
+                note: None,
+                region: None,
+                style: None,
             },
         ],
         qubits: vec![Qubit {
             id: 0,
             num_children: 2,
         }],
+        peak_live_qubits: None,
+        classical_wires: vec![],
     };
 
     expect![[r"
@@ -214,11 +269,16 @@ fn with_args() {
             controls: vec![],
             targets: vec![Register::quantum(0)],
             children: vec![],
+            note: None,
+            region: None,
+            style: None,
         }],
         qubits: vec![Qubit {
             id: 0,
             num_children: 0,
         }],
+        peak_live_qubits: None,
+        classical_wires: vec![],
     };
 
     // This looks wonky because the gate label is longer
@@ -241,6 +301,9 @@ fn two_targets() {
             controls: vec![],
             targets: vec![Register::quantum(0), Register::quantum(2)],
             children: vec![],
+            note: None,
+            region: None,
+            style: None,
         }],
         qubits: vec![
             Qubit {
@@ -256,6 +319,8 @@ fn two_targets() {
                 num_children: 0,
             },
         ],
+        peak_live_qubits: None,
+        classical_wires: vec![],
     };
 
     // This looks wonky because the gate label is longer
@@ -267,3 +332,639 @@ fn two_targets() {
     "]]
     .assert_eq(&c.to_string());
 }
+
+#[test]
+fn resource_counts() {
+    let c = Circuit {
+        operations: vec![
+            gate("H", vec![], vec![Register::quantum(0)]),
+            gate("T", vec![], vec![Register::quantum(0)]),
+            gate("X", vec![Register::quantum(0)], vec![Register::quantum(1)]),
+            Operation {
+                is_adjoint: true,
+                ..gate("T", vec![], vec![Register::quantum(1)])
+            },
+            gate(
+                "X",
+                vec![Register::quantum(0), Register::quantum(1)],
+                vec![Register::quantum(2)],
+            ),
+            Operation {
+                display_args: Some("0.5000".to_string()),
+                ..gate("rz", vec![], vec![Register::quantum(2)])
+            },
+            gate("X", vec![Register::quantum(1)], vec![Register::quantum(2)]),
+            gate(
+                "X",
+                vec![Register::classical(0, 0)],
+                vec![Register::quantum(2)],
+            ),
+        ],
+        qubits: vec![],
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+
+    assert_eq!(
+        c.resource_counts(),
+        ResourceCounts {
+            t_count: 2,
+            cnot_count: 2,
+            rotation_count: 1,
+        }
+    );
+}
+
+#[test]
+fn merge_adjacent_rotations() {
+    let rz = |theta: &str, q_id: usize| Operation {
+        display_args: Some(theta.to_string()),
+        ..gate("rz", vec![], vec![Register::quantum(q_id)])
+    };
+    let c = Circuit {
+        operations: vec![rz("0.5000", 0), rz("0.5000", 0)],
+        qubits: vec![Qubit {
+            id: 0,
+            num_children: 0,
+        }],
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+
+    expect![[r"
+        q_0     rz(1.0000)
+    "]]
+    .assert_eq(&c.merge_adjacent_rotations().to_string());
+}
+
+#[test]
+fn merge_adjacent_rotations_blocked_by_non_numeric_args() {
+    let rz = |theta: &str| Operation {
+        display_args: Some(theta.to_string()),
+        ..gate("rz", vec![], vec![Register::quantum(0)])
+    };
+    let c = Circuit {
+        operations: vec![rz("theta"), rz("0.5000")],
+        qubits: vec![Qubit {
+            id: 0,
+            num_children: 0,
+        }],
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+
+    assert_eq!(c.merge_adjacent_rotations(), c);
+}
+
+#[test]
+fn merge_adjacent_rotations_blocked_by_classically_controlled_second_rotation() {
+    let rz = |controls: Vec<Register>| Operation {
+        display_args: Some("0.5000".to_string()),
+        ..gate("rz", controls, vec![Register::quantum(0)])
+    };
+    let c = Circuit {
+        operations: vec![rz(vec![]), rz(vec![Register::classical(1, 0)])],
+        qubits: vec![
+            Qubit {
+                id: 0,
+                num_children: 0,
+            },
+            Qubit {
+                id: 1,
+                num_children: 1,
+            },
+        ],
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+
+    assert_eq!(c.merge_adjacent_rotations(), c);
+}
+
+#[test]
+fn route_lnn_inserts_swaps_for_non_adjacent_qubits() {
+    let c = Circuit {
+        operations: vec![gate(
+            "X",
+            vec![Register::quantum(0)],
+            vec![Register::quantum(3)],
+        )],
+        qubits: (0..4)
+            .map(|id| Qubit {
+                id,
+                num_children: 0,
+            })
+            .collect(),
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+
+    let routed = c.route_lnn(4);
+    let ops = routed
+        .operations
+        .iter()
+        .map(|op| {
+            let q_ids =
+                |registers: &[Register]| registers.iter().map(|r| r.q_id).collect::<Vec<_>>();
+            format!(
+                "{} {:?} {:?}",
+                op.gate,
+                q_ids(&op.controls),
+                q_ids(&op.targets)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Two SWAPs move qubit 0 next to qubit 3, after which the CX acts on adjacent wires
+    expect![[r#"
+        [
+            "SWAP [] [0, 1]",
+            "SWAP [] [1, 2]",
+            "X [2] [3]",
+        ]
+    "#]]
+    .assert_debug_eq(&ops);
+}
+
+#[test]
+fn route_lnn_keeps_annotations_of_routed_operations() {
+    let c = Circuit {
+        operations: vec![Operation {
+            note: Some("physical".to_string()),
+            region: Some("Main/Entangle".to_string()),
+            style: Some("red".to_string()),
+            ..gate("X", vec![Register::quantum(0)], vec![Register::quantum(2)])
+        }],
+        qubits: (0..3)
+            .map(|id| Qubit {
+                id,
+                num_children: 0,
+            })
+            .collect(),
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+
+    let routed = c.route_lnn(3);
+    let routed_op = routed
+        .operations
+        .iter()
+        .find(|op| op.gate == "X")
+        .expect("routed circuit should contain the X gate");
+    assert_eq!(routed_op.note.as_deref(), Some("physical"));
+    assert_eq!(routed_op.region.as_deref(), Some("Main/Entangle"));
+    assert_eq!(routed_op.style.as_deref(), Some("red"));
+}
+
+#[test]
+fn static_prefix_len_stops_at_first_classically_controlled_operation() {
+    let c = Circuit {
+        operations: vec![
+            gate("H", vec![], vec![Register::quantum(0)]),
+            gate("X", vec![Register::quantum(0)], vec![Register::quantum(1)]),
+            gate(
+                "Measure",
+                vec![Register::quantum(0)],
+                vec![Register::classical(0, 0)],
+            ),
+            gate("H", vec![], vec![Register::quantum(1)]),
+            gate(
+                "X",
+                vec![Register::classical(0, 0)],
+                vec![Register::quantum(1)],
+            ),
+            gate("H", vec![], vec![Register::quantum(0)]),
+        ],
+        qubits: vec![
+            Qubit {
+                id: 0,
+                num_children: 1,
+            },
+            Qubit {
+                id: 1,
+                num_children: 0,
+            },
+        ],
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+
+    assert_eq!(c.static_prefix_len(), 4);
+}
+
+#[test]
+fn decompose_ccx_expands_toffoli_into_cx_and_t_gates() {
+    let c = Circuit {
+        operations: vec![gate(
+            "CX",
+            vec![Register::quantum(0), Register::quantum(1)],
+            vec![Register::quantum(2)],
+        )],
+        qubits: (0..3)
+            .map(|id| Qubit {
+                id,
+                num_children: 0,
+            })
+            .collect(),
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+
+    let decomposed = c.decompose_ccx();
+    let counts = decomposed.resource_counts();
+    assert_eq!(counts.cnot_count, 6);
+    assert_eq!(counts.t_count, 7);
+    expect![[r#"
+        q_0    ─────────────────────── ● ───────────────────────── ● ─────────── ● ──── T ──── ● ──
+        q_1    ───────── ● ────────────┼──────────── ● ────────────┼───── T ──── X ─── T' ──── X ──
+        q_2    ── H ──── X ─── T' ──── X ──── T ──── X ─── T' ──── X ──── T ──── H ────────────────
+    "#]]
+    .assert_eq(&decomposed.to_string());
+}
+
+#[test]
+fn first_measurement_finds_index_of_first_measurement() {
+    let mut c = Circuit {
+        operations: vec![
+            gate("H", vec![], vec![Register::quantum(0)]),
+            gate("X", vec![], vec![Register::quantum(0)]),
+        ],
+        qubits: vec![Qubit {
+            id: 0,
+            num_children: 1,
+        }],
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+    assert_eq!(c.first_measurement(), None);
+
+    c.operations
+        .push(gate("Measure", vec![], vec![Register::classical(0, 0)]));
+    c.operations
+        .push(gate("Measure", vec![], vec![Register::classical(0, 0)]));
+    assert_eq!(c.first_measurement(), Some(2));
+}
+
+#[test]
+fn find_operations_returns_indices_of_matching_operations() {
+    let c = Circuit {
+        operations: vec![
+            gate("Measure", vec![], vec![Register::classical(0, 0)]),
+            gate("H", vec![], vec![Register::quantum(1)]),
+            gate("X", vec![], vec![Register::quantum(0)]),
+            gate("Measure", vec![], vec![Register::classical(1, 0)]),
+        ],
+        qubits: vec![
+            Qubit {
+                id: 0,
+                num_children: 1,
+            },
+            Qubit {
+                id: 1,
+                num_children: 1,
+            },
+        ],
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+    assert_eq!(c.find_operations(|op| op.is_measurement), vec![0, 3]);
+    assert_eq!(
+        c.find_operations(|op| op.targets.contains(&Register::quantum(1))),
+        vec![1]
+    );
+    assert!(c.find_operations(|op| op.is_adjoint).is_empty());
+}
+
+#[test]
+fn canonicalize_orders_commuting_gates_deterministically() {
+    let single = |name: &str, q_id: usize| gate(name, vec![], vec![Register::quantum(q_id)]);
+    let cx = |ctl: usize, q_id: usize| {
+        gate(
+            "X",
+            vec![Register::quantum(ctl)],
+            vec![Register::quantum(q_id)],
+        )
+    };
+    let circuit = |operations| Circuit {
+        operations,
+        qubits: (0..3)
+            .map(|id| Qubit {
+                id,
+                num_children: 0,
+            })
+            .collect(),
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+    let a = circuit(vec![
+        single("H", 0),
+        single("X", 2),
+        single("T", 1),
+        cx(0, 1),
+        single("Z", 2),
+    ]);
+    let b = circuit(vec![
+        single("X", 2),
+        single("T", 1),
+        single("Z", 2),
+        single("H", 0),
+        cx(0, 1),
+    ]);
+    assert_ne!(a, b);
+    assert_eq!(a.canonicalize(), b.canonicalize());
+
+    // Gates that share a wire keep their relative order.
+    let c = circuit(vec![single("H", 0), single("T", 0)]);
+    let d = circuit(vec![single("T", 0), single("H", 0)]);
+    assert_ne!(c.canonicalize(), d.canonicalize());
+}
+
+#[test]
+fn expand_resets_replaces_reset_with_measurement_and_classically_controlled_x() {
+    let c = Circuit {
+        operations: vec![gate(KET_ZERO, vec![], vec![Register::quantum(0)])],
+        qubits: vec![Qubit {
+            id: 0,
+            num_children: 0,
+        }],
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+
+    let expanded = c.expand_resets();
+    assert_eq!(
+        expanded.operations[0].gate,
+        Config::default().measurement_symbol
+    );
+    assert_eq!(
+        expanded.operations[1].controls,
+        vec![Register::classical(0, 0)]
+    );
+    expect![[r"
+        q_0    ── M ──── X ──
+                  ╘═════ ● ══
+    "]]
+    .assert_eq(&expanded.to_string());
+}
+
+#[test]
+fn expand_resets_names_measurements_like_existing_ones() {
+    let c = Circuit {
+        operations: vec![
+            Operation {
+                is_controlled: false,
+                is_measurement: true,
+                ..gate(
+                    "MZ",
+                    vec![Register::quantum(0)],
+                    vec![Register::classical(0, 0)],
+                )
+            },
+            gate(KET_ZERO, vec![], vec![Register::quantum(0)]),
+        ],
+        qubits: vec![Qubit {
+            id: 0,
+            num_children: 1,
+        }],
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+
+    let expanded = c.expand_resets();
+    assert_eq!(expanded.operations[1].gate, "MZ");
+    assert_eq!(
+        expanded.operations[2].controls,
+        vec![Register::classical(0, 1)]
+    );
+}
+
+#[test]
+fn equivalent_up_to_relabeling_matches_circuits_with_swapped_qubits() {
+    let circuit = |q0: usize, q1: usize| Circuit {
+        operations: vec![
+            gate("H", vec![], vec![Register::quantum(q0)]),
+            gate(
+                "X",
+                vec![Register::quantum(q0)],
+                vec![Register::quantum(q1)],
+            ),
+            gate(
+                "Measure",
+                vec![Register::quantum(q1)],
+                vec![Register::classical(q1, 0)],
+            ),
+        ],
+        qubits: vec![
+            Qubit {
+                id: q0,
+                num_children: 0,
+            },
+            Qubit {
+                id: q1,
+                num_children: 1,
+            },
+        ],
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+
+    assert!(circuit(0, 1).equivalent_up_to_relabeling(&circuit(1, 0)));
+    assert!(circuit(0, 1).equivalent_up_to_relabeling(&circuit(0, 1)));
+
+    let mut reversed_cnot = circuit(1, 0);
+    reversed_cnot.operations[1] = gate("X", vec![Register::quantum(0)], vec![Register::quantum(1)]);
+    assert!(!circuit(0, 1).equivalent_up_to_relabeling(&reversed_cnot));
+}
+
+#[test]
+fn per_qubit_gate_counts_counts_operations_touching_each_qubit() {
+    let c = Circuit {
+        operations: vec![
+            gate("H", vec![], vec![Register::quantum(0)]),
+            gate("X", vec![Register::quantum(0)], vec![Register::quantum(1)]),
+            gate(
+                "Measure",
+                vec![Register::quantum(0)],
+                vec![Register::classical(0, 0)],
+            ),
+            gate(
+                "Measure",
+                vec![Register::quantum(1)],
+                vec![Register::classical(1, 0)],
+            ),
+            gate(
+                "X",
+                vec![Register::classical(0, 0)],
+                vec![Register::quantum(1)],
+            ),
+        ],
+        qubits: vec![
+            Qubit {
+                id: 0,
+                num_children: 1,
+            },
+            Qubit {
+                id: 1,
+                num_children: 1,
+            },
+        ],
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+
+    let counts = c
+        .per_qubit_gate_counts()
+        .iter()
+        .map(|(q, count)| (q, *count))
+        .collect::<Vec<_>>();
+    // H, CX and the measurement on qubit 0; CX, the measurement and the conditional X on qubit 1.
+    assert_eq!(counts, vec![(0, 3), (1, 3)]);
+}
+
+#[test]
+fn apply_rewrite_rules_cancels_self_inverse_pairs() {
+    let c = Circuit {
+        operations: vec![
+            gate("H", vec![], vec![Register::quantum(0)]),
+            gate("X", vec![Register::quantum(0)], vec![Register::quantum(1)]),
+            gate("Z", vec![], vec![Register::quantum(1)]),
+            gate("Z", vec![], vec![Register::quantum(1)]),
+            gate("X", vec![Register::quantum(0)], vec![Register::quantum(1)]),
+            gate("X", vec![], vec![Register::quantum(1)]),
+        ],
+        qubits: (0..2)
+            .map(|id| Qubit {
+                id,
+                num_children: 0,
+            })
+            .collect(),
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+
+    let rewritten = c.apply_rewrite_rules(&[RewriteRule::cancel_self_inverse_pairs()]);
+    expect![[r"
+        q_0    ── H ──
+        q_1    ── X ──
+    "]]
+    .assert_eq(&rewritten.to_string());
+}
+
+#[test]
+fn to_qir_comments_names_intrinsics_and_indices_for_measured_bell_circuit() {
+    let measure = |q_id: usize| {
+        gate(
+            "Measure",
+            vec![Register::quantum(q_id)],
+            vec![Register::classical(q_id, 0)],
+        )
+    };
+    let c = Circuit {
+        operations: vec![
+            gate("H", vec![], vec![Register::quantum(0)]),
+            gate("X", vec![Register::quantum(0)], vec![Register::quantum(1)]),
+            measure(0),
+            measure(1),
+        ],
+        qubits: (0..2)
+            .map(|id| Qubit {
+                id,
+                num_children: 1,
+            })
+            .collect(),
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+
+    expect![[r"
+        ; __quantum__qis__h__body(qubit 0)
+        ; __quantum__qis__cnot__body(qubit 0, qubit 1)
+        ; __quantum__qis__mz__body(qubit 0, result 0)
+        ; __quantum__qis__mz__body(qubit 1, result 1)
+    "]]
+    .assert_eq(&c.to_qir_comments());
+}
+
+#[test]
+fn drop_global_phase_removes_uncontrolled_global_phase_gates_only() {
+    let gate_with_args =
+        |name: &str, args: &str, controls: Vec<Register>, targets: Vec<Register>| Operation {
+            display_args: Some(args.to_string()),
+            ..gate(name, controls, targets)
+        };
+    let c = Circuit {
+        operations: vec![
+            gate_with_args("rx", "0.5000", vec![], vec![Register::quantum(0)]),
+            gate_with_args("GPhase", "0.5000", vec![], vec![]),
+            gate_with_args("GPhase", "0.5000", vec![Register::quantum(0)], vec![]),
+            gate_with_args(
+                "Exp",
+                "[PauliI, PauliI], 0.5000",
+                vec![],
+                vec![Register::quantum(0), Register::quantum(1)],
+            ),
+            gate_with_args(
+                "Exp",
+                "[PauliI, PauliZ], 0.5000",
+                vec![],
+                vec![Register::quantum(0), Register::quantum(1)],
+            ),
+        ],
+        qubits: (0..2)
+            .map(|id| Qubit {
+                id,
+                num_children: 0,
+            })
+            .collect(),
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+
+    let dropped = c.drop_global_phase();
+    let gates = dropped
+        .operations
+        .iter()
+        .map(|op| (op.gate.as_str(), op.controls.len()))
+        .collect::<Vec<_>>();
+    assert_eq!(gates, vec![("rx", 0), ("GPhase", 1), ("Exp", 0)]);
+    assert_eq!(
+        dropped.operations[2].display_args.as_deref(),
+        Some("[PauliI, PauliZ], 0.5000")
+    );
+}
+
+#[test]
+fn to_qir_comments_renders_classically_controlled_gate_as_conditional() {
+    let c = Circuit {
+        operations: vec![
+            gate(
+                "Measure",
+                vec![Register::quantum(0)],
+                vec![Register::classical(0, 0)],
+            ),
+            gate(
+                "X",
+                vec![Register::classical(0, 0)],
+                vec![Register::quantum(1)],
+            ),
+        ],
+        qubits: vec![
+            Qubit {
+                id: 0,
+                num_children: 1,
+            },
+            Qubit {
+                id: 1,
+                num_children: 0,
+            },
+        ],
+        peak_live_qubits: None,
+        classical_wires: vec![],
+    };
+
+    expect![[r"
+        ; __quantum__qis__mz__body(qubit 0, result 0)
+        ; __quantum__qis__x__body(qubit 1) conditioned on result 0
+    "]]
+    .assert_eq(&c.to_qir_comments());
+}
@@ -0,0 +1,203 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#[cfg(test)]
+mod tests;
+
+use num_bigint::BigUint;
+use num_complex::Complex;
+use qsc_eval::{backend::Backend, val::Value};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Backend wrapper that folds every gate applied to the inner backend into a 64-bit fingerprint,
+/// e.g. to cache the results of a program or to detect when the gates it applies change.
+/// The fingerprint is computed with FNV-1a over the gate names and their arguments, so it is stable
+/// across runs and platforms, and two runs applying the same gates in the same order always match.
+/// Every call is still forwarded to the inner backend, so the results it produces are not affected.
+pub struct FingerprintBackend<B> {
+    pub inner: B,
+    hash: u64,
+}
+
+impl<B> FingerprintBackend<B>
+where
+    B: Backend,
+{
+    #[must_use]
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            hash: FNV_OFFSET_BASIS,
+        }
+    }
+
+    /// The fingerprint of the gates applied so far.
+    #[must_use]
+    pub fn fingerprint(&self) -> u64 {
+        self.hash
+    }
+
+    fn record(&mut self, gate: &str, args: &[u64]) {
+        let arg_bytes = args.iter().flat_map(|arg| arg.to_le_bytes());
+        // The gate name is terminated so that it cannot run into the bytes of its arguments.
+        for byte in gate.bytes().chain([0]).chain(arg_bytes) {
+            self.hash ^= u64::from(byte);
+            self.hash = self.hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+}
+
+impl<B> Backend for FingerprintBackend<B>
+where
+    B: Backend,
+{
+    type ResultType = B::ResultType;
+
+    fn ccx(&mut self, ctl0: usize, ctl1: usize, q: usize) {
+        self.record("ccx", &[ctl0 as u64, ctl1 as u64, q as u64]);
+        self.inner.ccx(ctl0, ctl1, q);
+    }
+
+    fn cx(&mut self, ctl: usize, q: usize) {
+        self.record("cx", &[ctl as u64, q as u64]);
+        self.inner.cx(ctl, q);
+    }
+
+    fn cy(&mut self, ctl: usize, q: usize) {
+        self.record("cy", &[ctl as u64, q as u64]);
+        self.inner.cy(ctl, q);
+    }
+
+    fn cz(&mut self, ctl: usize, q: usize) {
+        self.record("cz", &[ctl as u64, q as u64]);
+        self.inner.cz(ctl, q);
+    }
+
+    fn h(&mut self, q: usize) {
+        self.record("h", &[q as u64]);
+        self.inner.h(q);
+    }
+
+    fn m(&mut self, q: usize) -> Self::ResultType {
+        self.record("m", &[q as u64]);
+        self.inner.m(q)
+    }
+
+    fn mresetz(&mut self, q: usize) -> Self::ResultType {
+        self.record("mresetz", &[q as u64]);
+        self.inner.mresetz(q)
+    }
+
+    fn reset(&mut self, q: usize) {
+        self.record("reset", &[q as u64]);
+        self.inner.reset(q);
+    }
+
+    fn rx(&mut self, theta: f64, q: usize) {
+        self.record("rx", &[theta.to_bits(), q as u64]);
+        self.inner.rx(theta, q);
+    }
+
+    fn rxx(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.record("rxx", &[theta.to_bits(), q0 as u64, q1 as u64]);
+        self.inner.rxx(theta, q0, q1);
+    }
+
+    fn ry(&mut self, theta: f64, q: usize) {
+        self.record("ry", &[theta.to_bits(), q as u64]);
+        self.inner.ry(theta, q);
+    }
+
+    fn ryy(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.record("ryy", &[theta.to_bits(), q0 as u64, q1 as u64]);
+        self.inner.ryy(theta, q0, q1);
+    }
+
+    fn rz(&mut self, theta: f64, q: usize) {
+        self.record("rz", &[theta.to_bits(), q as u64]);
+        self.inner.rz(theta, q);
+    }
+
+    fn rzz(&mut self, theta: f64, q0: usize, q1: usize) {
+        self.record("rzz", &[theta.to_bits(), q0 as u64, q1 as u64]);
+        self.inner.rzz(theta, q0, q1);
+    }
+
+    fn sadj(&mut self, q: usize) {
+        self.record("sadj", &[q as u64]);
+        self.inner.sadj(q);
+    }
+
+    fn s(&mut self, q: usize) {
+        self.record("s", &[q as u64]);
+        self.inner.s(q);
+    }
+
+    fn swap(&mut self, q0: usize, q1: usize) {
+        self.record("swap", &[q0 as u64, q1 as u64]);
+        self.inner.swap(q0, q1);
+    }
+
+    fn tadj(&mut self, q: usize) {
+        self.record("tadj", &[q as u64]);
+        self.inner.tadj(q);
+    }
+
+    fn t(&mut self, q: usize) {
+        self.record("t", &[q as u64]);
+        self.inner.t(q);
+    }
+
+    fn x(&mut self, q: usize) {
+        self.record("x", &[q as u64]);
+        self.inner.x(q);
+    }
+
+    fn y(&mut self, q: usize) {
+        self.record("y", &[q as u64]);
+        self.inner.y(q);
+    }
+
+    fn z(&mut self, q: usize) {
+        self.record("z", &[q as u64]);
+        self.inner.z(q);
+    }
+
+    fn qubit_allocate(&mut self) -> usize {
+        self.inner.qubit_allocate()
+    }
+
+    fn qubit_release(&mut self, q: usize) {
+        self.inner.qubit_release(q);
+    }
+
+    fn capture_quantum_state(&mut self) -> (Vec<(BigUint, Complex<f64>)>, usize) {
+        self.inner.capture_quantum_state()
+    }
+
+    fn qubit_is_zero(&mut self, q: usize) -> bool {
+        self.inner.qubit_is_zero(q)
+    }
+
+    fn custom_intrinsic(&mut self, name: &str, arg: Value) -> Option<Result<Value, String>> {
+        self.inner.custom_intrinsic(name, arg)
+    }
+
+    fn set_seed(&mut self, seed: Option<u64>) {
+        self.inner.set_seed(seed);
+    }
+
+    fn set_classical(&mut self, name: &str, result: usize) {
+        self.inner.set_classical(name, result);
+    }
+
+    fn enter_region(&mut self, label: &str, is_library_call: bool) {
+        self.inner.enter_region(label, is_library_call);
+    }
+
+    fn exit_region(&mut self) {
+        self.inner.exit_region();
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+use super::*;
+use crate::{Builder, Config};
+
+fn run(program: impl Fn(&mut FingerprintBackend<Builder>)) -> u64 {
+    let mut backend = FingerprintBackend::new(Builder::new(Config::default()));
+    program(&mut backend);
+    backend.fingerprint()
+}
+
+fn bell(backend: &mut FingerprintBackend<Builder>) {
+    let q0 = backend.qubit_allocate();
+    let q1 = backend.qubit_allocate();
+    backend.h(q0);
+    backend.cx(q0, q1);
+    backend.m(q0);
+    backend.m(q1);
+}
+
+#[test]
+fn same_program_has_same_fingerprint() {
+    assert_eq!(run(bell), run(bell));
+}
+
+#[test]
+fn modified_program_has_different_fingerprint() {
+    let modified = run(|backend| {
+        let q0 = backend.qubit_allocate();
+        let q1 = backend.qubit_allocate();
+        backend.h(q0);
+        backend.cx(q1, q0);
+        backend.m(q0);
+        backend.m(q1);
+    });
+    assert_ne!(run(bell), modified);
+}
+
+#[test]
+fn rotation_angle_is_part_of_fingerprint() {
+    let rotation = |theta: f64| {
+        run(move |backend| {
+            let q = backend.qubit_allocate();
+            backend.rx(theta, q);
+        })
+    };
+    assert_eq!(rotation(0.5), rotation(0.5));
+    assert_ne!(rotation(0.5), rotation(0.25));
+}
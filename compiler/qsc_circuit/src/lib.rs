@@ -1,9 +1,13 @@
 // Copyright (c) Microsoft Corporation.
 // Licensed under the MIT License.
 
+mod bounded_width;
 mod builder;
 mod circuit;
+mod fingerprint;
 pub mod operations;
 
+pub use bounded_width::BoundedWidthBackend;
 pub use builder::Builder;
-pub use circuit::{Circuit, Config, Operation};
+pub use circuit::{Circuit, ClassicalWire, Config, Operation, ResourceCounts, RewriteRule};
+pub use fingerprint::FingerprintBackend;
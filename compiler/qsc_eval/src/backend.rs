@@ -45,6 +45,17 @@ pub trait Backend {
     }
 
     fn set_seed(&mut self, _seed: Option<u64>) {}
+
+    /// Called when the measurement result with the given id is stored into the named classical variable.
+    fn set_classical(&mut self, _name: &str, _result: usize) {}
+
+    /// Called when the evaluator enters the body of a callable, with the callable name as the label of the region.
+    /// A library call is one to a callable that belongs to a different package than its caller (e.g. a standard
+    /// library operation called from user code).
+    fn enter_region(&mut self, _label: &str, _is_library_call: bool) {}
+
+    /// Called when the evaluator leaves the region that was entered last.
+    fn exit_region(&mut self) {}
 }
 
 /// Default backend used when targeting sparse simulation.
@@ -407,4 +418,19 @@ where
         self.chained.set_seed(seed);
         self.main.set_seed(seed);
     }
+
+    fn set_classical(&mut self, name: &str, result: usize) {
+        self.chained.set_classical(name, result);
+        self.main.set_classical(name, result);
+    }
+
+    fn enter_region(&mut self, label: &str, is_library_call: bool) {
+        self.chained.enter_region(label, is_library_call);
+        self.main.enter_region(label, is_library_call);
+    }
+
+    fn exit_region(&mut self) {
+        self.chained.exit_region();
+        self.main.exit_region();
+    }
 }
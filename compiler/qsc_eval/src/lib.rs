@@ -409,6 +409,8 @@ pub struct State {
     call_stack: CallStack,
     current_span: Span,
     rng: RefCell<StdRng>,
+    /// The number of regions entered on the backend by calls that have not returned yet.
+    open_regions: usize,
 }
 
 impl State {
@@ -432,6 +434,7 @@ impl State {
             call_stack: CallStack::default(),
             current_span: Span::default(),
             rng,
+            open_regions: 0,
         }
     }
 
@@ -463,6 +466,12 @@ impl State {
         self.exec_graph_stack.pop();
     }
 
+    fn exit_open_regions(&mut self, sim: &mut impl Backend<ResultType = impl Into<val::Result>>) {
+        for _ in 0..std::mem::take(&mut self.open_regions) {
+            sim.exit_region();
+        }
+    }
+
     fn push_scope(&mut self, env: &mut Env) {
         env.push_scope(self.call_stack.len());
     }
@@ -533,13 +542,16 @@ impl State {
             let res = match exec_graph.get(self.idx as usize) {
                 Some(ExecGraphNode::Bind(pat)) => {
                     self.idx += 1;
-                    self.eval_bind(env, globals, *pat);
+                    self.eval_bind(env, globals, sim, *pat);
                     continue;
                 }
                 Some(ExecGraphNode::Expr(expr)) => {
                     self.idx += 1;
-                    self.eval_expr(env, sim, globals, out, *expr)
-                        .map_err(|e| (e, self.get_stack_frames()))?;
+                    self.eval_expr(env, sim, globals, out, *expr).map_err(|e| {
+                        // The calls in progress never return, so their regions are exited here instead.
+                        self.exit_open_regions(sim);
+                        (e, self.get_stack_frames())
+                    })?;
                     continue;
                 }
                 Some(ExecGraphNode::Stmt(stmt)) => {
@@ -600,6 +612,11 @@ impl State {
                 }
                 Some(ExecGraphNode::Ret) => {
                     self.leave_frame();
+                    // The graph of top-level statements also ends in a return, without a region to exit.
+                    if self.open_regions > 0 {
+                        self.open_regions -= 1;
+                        sim.exit_region();
+                    }
                     env.leave_scope();
                     continue;
                 }
@@ -822,11 +839,40 @@ impl State {
         self.update_binding(env, globals, lhs, rhs)
     }
 
-    fn eval_bind(&mut self, env: &mut Env, globals: &impl PackageStoreLookup, pat: PatId) {
+    fn eval_bind(
+        &mut self,
+        env: &mut Env,
+        globals: &impl PackageStoreLookup,
+        sim: &mut impl Backend,
+        pat: PatId,
+    ) {
         let val = self.take_val_register();
+        self.notify_classical_bindings(globals, sim, pat, &val);
         self.bind_value(env, globals, pat, val);
     }
 
+    /// Lets the backend know about every measurement result that gets stored into a named variable. The results in an
+    /// array are named after the variable and their index (e.g. `rs[0]`). Results that are plain values rather than
+    /// identifiers (e.g. the ones of a simulator) cannot be related to a measurement, so they are not notified.
+    fn notify_classical_bindings(
+        &self,
+        globals: &impl PackageStoreLookup,
+        sim: &mut impl Backend,
+        pat: PatId,
+        val: &Value,
+    ) {
+        let pat = globals.get_pat((self.package, pat).into());
+        match (&pat.kind, val) {
+            (PatKind::Bind(variable), val) => notify_classical_value(sim, &variable.name, val),
+            (PatKind::Tuple(tup), Value::Tuple(vals)) => {
+                for (pat, val) in tup.iter().zip(vals.iter()) {
+                    self.notify_classical_bindings(globals, sim, *pat, val);
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn eval_binop(&mut self, op: BinOp, span: Span) -> Result<(), Error> {
         match op {
             BinOp::Add => self.eval_binop_simple(eval_binop_add),
@@ -933,8 +979,11 @@ impl State {
                     Spec::CtlAdj => specialized_implementation.ctl_adj.as_ref(),
                 }
                 .expect("missing specialization should be a compilation error");
+                let is_library_call = callee_id.package != self.package;
                 self.push_frame(spec_decl.exec_graph.clone(), callee_id, functor);
                 self.push_scope(env);
+                sim.enter_region(&callee.name.name, is_library_call);
+                self.open_regions += 1;
 
                 self.bind_args_for_spec(
                     env,
@@ -1366,6 +1415,18 @@ fn resolve_binding(env: &Env, package: PackageId, res: Res, span: Span) -> Resul
     })
 }
 
+fn notify_classical_value(sim: &mut impl Backend, name: &str, val: &Value) {
+    match val {
+        Value::Result(val::Result::Id(id)) => sim.set_classical(name, *id),
+        Value::Array(vals) => {
+            for (index, val) in vals.iter().enumerate() {
+                notify_classical_value(sim, &format!("{name}[{index}]"), val);
+            }
+        }
+        _ => {}
+    }
+}
+
 fn spec_from_functor_app(functor: FunctorApp) -> Spec {
     match (functor.adjoint, functor.controlled) {
         (false, 0) => Spec::Body,
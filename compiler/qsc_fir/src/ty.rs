@@ -336,7 +336,7 @@ impl Display for FunctorSet {
 }
 
 /// The value of a functor set.
-#[derive(Clone, Copy, Debug, Default, Hash, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
 pub enum FunctorSetValue {
     /// The empty set.
     #[default]
@@ -350,6 +350,13 @@ pub enum FunctorSetValue {
 }
 
 impl FunctorSetValue {
+    /// The order in which the specializations that correspond to each functor set value are displayed: body, adjoint,
+    /// controlled and controlled adjoint.
+    #[must_use]
+    pub fn display_order() -> [FunctorSetValue; 4] {
+        [Self::Empty, Self::Adj, Self::Ctl, Self::CtlAdj]
+    }
+
     /// True if this set contains the functor.
     #[must_use]
     pub fn contains(&self, functor: &Functor) -> bool {
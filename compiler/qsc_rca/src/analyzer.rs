@@ -2,15 +2,35 @@
 // Licensed under the MIT License.
 
 use crate::{
-    core, cyclic_callables, overrider::Overrider,
-    scaffolding::InternalPackageStoreComputeProperties, PackageStoreComputeProperties,
+    common::{derive_callable_input_params, InputParamIndex},
+    core, cyclic_callables,
+    overrider::Overrider,
+    scaffolding::InternalPackageStoreComputeProperties,
+    ComputeKind, ComputePropertiesLookup, ExtendedFeatures, ItemComputeProperties,
+    PackageStoreComputeProperties, RuntimeFeatureFlags, ValueKind,
 };
-use qsc_fir::fir::{PackageId, PackageStore};
+use qsc_fir::{
+    fir::{
+        CallableDecl, ExprId, Global, PackageId, PackageStore, PackageStoreLookup, StmtId,
+        StoreExprId, StoreItemId,
+    },
+    ty::FunctorSetValue,
+};
+
+/// A hook used to derive the vendor-specific runtime features of intrinsic callables.
+pub type IntrinsicFeaturesHook<'a> = &'a dyn Fn(&CallableDecl) -> ExtendedFeatures;
+
+/// A hook used to classify intrinsic callables that only have diagnostic effects (e.g. `Message` or `DumpMachine`), so
+/// they do not add to the runtime capabilities required by a program.
+pub type DebugOnlyIntrinsicsHook<'a> = &'a dyn Fn(&CallableDecl) -> bool;
 
 /// A runtime capabilities analyzer.
 pub struct Analyzer<'a> {
     package_store: &'a PackageStore,
     scaffolding: InternalPackageStoreComputeProperties,
+    intrinsic_features_hook: Option<IntrinsicFeaturesHook<'a>>,
+    debug_only_intrinsics_hook: Option<DebugOnlyIntrinsicsHook<'a>>,
+    strict_callee_resolution: bool,
 }
 
 impl<'a> Analyzer<'a> {
@@ -19,6 +39,9 @@ impl<'a> Analyzer<'a> {
         Self {
             package_store,
             scaffolding: InternalPackageStoreComputeProperties::init(package_store),
+            intrinsic_features_hook: None,
+            debug_only_intrinsics_hook: None,
+            strict_callee_resolution: false,
         }
     }
 
@@ -30,11 +53,104 @@ impl<'a> Analyzer<'a> {
         Self {
             package_store,
             scaffolding: package_store_compute_properties.into(),
+            intrinsic_features_hook: None,
+            debug_only_intrinsics_hook: None,
+            strict_callee_resolution: false,
         }
     }
 
+    /// Discards the compute properties accumulated by previous analyses so the analyzer can be reused as if it had
+    /// just been initialized, while keeping its hooks. The per-analysis state (e.g. the active contexts) only lives
+    /// while an analysis runs, so it does not need to be cleared here.
+    pub fn reset(&mut self) {
+        self.scaffolding = InternalPackageStoreComputeProperties::init(self.package_store);
+    }
+
+    /// Sets a hook that is called for each intrinsic callable to determine the vendor-specific runtime features it
+    /// uses.
+    #[must_use]
+    pub fn with_intrinsic_features_hook(mut self, hook: IntrinsicFeaturesHook<'a>) -> Self {
+        self.intrinsic_features_hook = Some(hook);
+        self
+    }
+
+    /// Sets a hook that is called for each intrinsic callable to determine whether it is debug-only, in which case
+    /// applying it does not use any runtime feature regardless of its arguments.
+    #[must_use]
+    pub fn with_debug_only_intrinsics_hook(mut self, hook: DebugOnlyIntrinsicsHook<'a>) -> Self {
+        self.debug_only_intrinsics_hook = Some(hook);
+        self
+    }
+
+    /// Makes callee expressions that cannot be resolved to a specific callable an error when analyzing through
+    /// [`Analyzer::try_analyze_all`], instead of conservatively assuming they use the
+    /// [`RuntimeFeatureFlags::CallToUnresolvedCallee`] runtime feature.
+    #[must_use]
+    pub fn with_strict_callee_resolution(mut self) -> Self {
+        self.strict_callee_resolution = true;
+        self
+    }
+
     #[must_use]
     pub fn analyze_all(self) -> PackageStoreComputeProperties {
+        self.analyze_all_with_progress(|_, _| {})
+    }
+
+    /// Analyzes all the packages in the store and also returns the union of the runtime features used anywhere in it,
+    /// which is useful for a quick audit of the capabilities a store requires.
+    #[must_use]
+    pub fn analyze_all_collecting(self) -> (PackageStoreComputeProperties, RuntimeFeatureFlags) {
+        let package_store_compute_properties = self.analyze_all();
+        let mut runtime_features = RuntimeFeatureFlags::empty();
+        for (_, package_compute_properties) in &package_store_compute_properties {
+            for (_, item_compute_properties) in package_compute_properties.items.iter() {
+                if let ItemComputeProperties::Callable(callable_compute_properties) =
+                    item_compute_properties
+                {
+                    for specialization_runtime_features in callable_compute_properties
+                        .per_specialization_features()
+                        .into_values()
+                    {
+                        runtime_features |= specialization_runtime_features;
+                    }
+                }
+            }
+            for (_, application_generator_set) in package_compute_properties.exprs.iter() {
+                runtime_features |= application_generator_set.inherent.runtime_features();
+            }
+        }
+        (package_store_compute_properties, runtime_features)
+    }
+
+    /// Analyzes all the packages in the store, invoking the progress callback each time an item is analyzed with the
+    /// number of items analyzed so far and the total number of items.
+    #[must_use]
+    pub fn analyze_all_with_progress(
+        self,
+        progress: impl FnMut(usize, usize),
+    ) -> PackageStoreComputeProperties {
+        let (package_store_compute_properties, _) = self.analyze_all_internal(progress);
+        package_store_compute_properties
+    }
+
+    /// Analyzes all the packages in the store like [`Analyzer::analyze_all`] does. When strict callee resolution is
+    /// enabled, the callee expressions that could not be resolved to a specific callable, including the ones in
+    /// library packages, are returned as an error instead.
+    pub fn try_analyze_all(self) -> Result<PackageStoreComputeProperties, Vec<StoreExprId>> {
+        let strict_callee_resolution = self.strict_callee_resolution;
+        let (package_store_compute_properties, unresolved_callees) =
+            self.analyze_all_internal(|_, _| {});
+        if strict_callee_resolution && !unresolved_callees.is_empty() {
+            Err(unresolved_callees)
+        } else {
+            Ok(package_store_compute_properties)
+        }
+    }
+
+    fn analyze_all_internal(
+        self,
+        progress: impl FnMut(usize, usize),
+    ) -> (PackageStoreComputeProperties, Vec<StoreExprId>) {
         // First, we populate the elements for which we override its compute properties.
         let overrider = Overrider::new(self.package_store, self.scaffolding);
         let scaffolding = overrider.populate_overrides();
@@ -46,8 +162,21 @@ impl<'a> Analyzer<'a> {
         let scaffolding = cyclic_callables_analyzer.analyze_all();
 
         // Now we can safely analyze the rest of the items.
-        let core_analyzer = core::Analyzer::new(self.package_store, scaffolding);
-        core_analyzer.analyze_all().into()
+        let core_analyzer = core::Analyzer::new(
+            self.package_store,
+            scaffolding,
+            self.intrinsic_features_hook,
+            self.debug_only_intrinsics_hook,
+        );
+        let (scaffolding, unresolved_callees) = core_analyzer.analyze_all_with_progress(progress);
+        let package_store_compute_properties: PackageStoreComputeProperties = scaffolding.into();
+        debug_assert!(
+            package_store_compute_properties
+                .verify_completeness(self.package_store)
+                .is_ok(),
+            "all blocks in the package store should have been analyzed"
+        );
+        (package_store_compute_properties, unresolved_callees)
     }
 
     #[must_use]
@@ -57,7 +186,107 @@ impl<'a> Analyzer<'a> {
         let cyclic_callables_analyzer =
             cyclic_callables::Analyzer::new(self.package_store, self.scaffolding);
         let scaffolding = cyclic_callables_analyzer.analyze_package(package_id);
-        let core_analyzer = core::Analyzer::new(self.package_store, scaffolding);
+        let core_analyzer = core::Analyzer::new(
+            self.package_store,
+            scaffolding,
+            self.intrinsic_features_hook,
+            self.debug_only_intrinsics_hook,
+        );
         core_analyzer.analyze_package(package_id).into()
     }
+
+    /// Analyzes a single expression in isolation, which is useful to get the compute kind of an expression without
+    /// analyzing the whole callable it belongs to. Locals bound outside of the expression, including their fields, are
+    /// considered classical. Like when analyzing a package, the compute properties of the dependencies of the package
+    /// are expected to be already populated.
+    pub fn analyze_expr_standalone(
+        &mut self,
+        package_id: PackageId,
+        expr_id: ExprId,
+    ) -> ComputeKind {
+        let scaffolding = std::mem::take(&mut self.scaffolding);
+        let cyclic_callables_analyzer =
+            cyclic_callables::Analyzer::new(self.package_store, scaffolding);
+        let scaffolding = cyclic_callables_analyzer.analyze_package(package_id);
+        let core_analyzer = core::Analyzer::new(
+            self.package_store,
+            scaffolding,
+            self.intrinsic_features_hook,
+            self.debug_only_intrinsics_hook,
+        );
+        let (compute_kind, scaffolding) =
+            core_analyzer.analyze_expr_standalone(package_id, expr_id);
+        self.scaffolding = scaffolding;
+        compute_kind
+    }
+
+    /// Analyzes the body specialization of a callable as if it were applied with exactly the specified input
+    /// parameters being dynamic and the rest being static, which is useful when the dynamism of the arguments is known
+    /// (e.g. from a call site) and the full set of parameter applications is not needed. Returns `None` if the item is
+    /// not a callable.
+    pub fn analyze_spec_with_dynamic_params(
+        &mut self,
+        id: StoreItemId,
+        dynamic: &[InputParamIndex],
+    ) -> Option<ComputeKind> {
+        let Some(Global::Callable(callable_decl)) = self.package_store.get_global(id) else {
+            return None;
+        };
+
+        let scaffolding = std::mem::take(&mut self.scaffolding);
+        let cyclic_callables_analyzer =
+            cyclic_callables::Analyzer::new(self.package_store, scaffolding);
+        let scaffolding = cyclic_callables_analyzer.analyze_package(id.package);
+        let core_analyzer = core::Analyzer::new(
+            self.package_store,
+            scaffolding,
+            self.intrinsic_features_hook,
+            self.debug_only_intrinsics_hook,
+        );
+        self.scaffolding = core_analyzer.analyze_package(id.package);
+
+        let input_params =
+            derive_callable_input_params(callable_decl, &self.package_store.get(id.package).pats);
+        let args_value_kinds = input_params
+            .iter()
+            .map(|input_param| {
+                if dynamic.contains(&input_param.index) {
+                    ValueKind::new_dynamic_from_type(&input_param.ty)
+                } else {
+                    ValueKind::new_static_from_type(&input_param.ty)
+                }
+            })
+            .collect::<Vec<_>>();
+        Some(
+            self.scaffolding
+                .get_spec((id, FunctorSetValue::Empty).into())
+                .apply(&args_value_kinds),
+        )
+    }
+
+    /// Analyzes a top-level statement that was just added to a package, like in a REPL session, without reanalyzing
+    /// the items of the package. The compute properties of the items the statement uses are reused if they are
+    /// already populated. Previous top-level statements are visited again so that the locals they bind keep their
+    /// compute kinds.
+    pub fn analyze_new_top_level_stmt(
+        &mut self,
+        package_id: PackageId,
+        stmt_id: StmtId,
+    ) -> ComputeKind {
+        let scaffolding = std::mem::take(&mut self.scaffolding);
+        let cyclic_callables_analyzer =
+            cyclic_callables::Analyzer::new(self.package_store, scaffolding);
+        let scaffolding = cyclic_callables_analyzer.analyze_package(package_id);
+        let core_analyzer = core::Analyzer::new(
+            self.package_store,
+            scaffolding,
+            self.intrinsic_features_hook,
+            self.debug_only_intrinsics_hook,
+        );
+        self.scaffolding = core_analyzer.analyze_top_level_stmt(package_id, stmt_id);
+        self.scaffolding
+            .get_stmt((package_id, stmt_id).into())
+            .inherent
+            .clone()
+    }
 }
@@ -4,8 +4,8 @@
 use crate::{
     common::{initialize_locals_map, InputParam, InputParamIndex, Local, LocalKind, LocalsLookup},
     scaffolding::InternalPackageComputeProperties,
-    ApplicationGeneratorSet, ComputeKind, QuantumProperties, RuntimeFeatureFlags, RuntimeKind,
-    ValueKind,
+    ApplicationGeneratorSet, ComputeKind, ExtendedFeatures, QuantumProperties, RuntimeFeatureFlags,
+    RuntimeKind, ValueKind,
 };
 use qsc_data_structures::index_map::IndexMap;
 use qsc_fir::{
@@ -57,7 +57,7 @@ impl GeneratorSetsBuilder {
                         return_type,
                         Some((
                             input_param.index,
-                            ValueKind::Array(RuntimeKind::Static, RuntimeKind::Dynamic),
+                            &ValueKind::Array(RuntimeKind::Static, RuntimeKind::Dynamic),
                         )),
                     );
                     let dynamic_content_static_size = ApplicationInstance::new(
@@ -66,7 +66,7 @@ impl GeneratorSetsBuilder {
                         return_type,
                         Some((
                             input_param.index,
-                            ValueKind::Array(RuntimeKind::Dynamic, RuntimeKind::Static),
+                            &ValueKind::Array(RuntimeKind::Dynamic, RuntimeKind::Static),
                         )),
                     );
                     let dynamic_content_dynamic_size = ApplicationInstance::new(
@@ -75,7 +75,7 @@ impl GeneratorSetsBuilder {
                         return_type,
                         Some((
                             input_param.index,
-                            ValueKind::Array(RuntimeKind::Dynamic, RuntimeKind::Dynamic),
+                            &ValueKind::Array(RuntimeKind::Dynamic, RuntimeKind::Dynamic),
                         )),
                     );
                     vec![
@@ -90,7 +90,7 @@ impl GeneratorSetsBuilder {
                         input_params,
                         controls,
                         return_type,
-                        Some((input_param.index, ValueKind::Element(RuntimeKind::Dynamic))),
+                        Some((input_param.index, &ValueKind::Element(RuntimeKind::Dynamic))),
                     )]
                 }
             };
@@ -127,6 +127,16 @@ impl GeneratorSetsBuilder {
             .expect("nth application instance does not exist")
     }
 
+    /// Gets the maximum dynamic scope depth reached across all the application instances.
+    pub fn max_dynamic_scope_depth(&self) -> usize {
+        self.application_instances
+            .iter()
+            .flatten()
+            .map(|application_instance| application_instance.max_dynamic_scope_depth)
+            .max()
+            .unwrap_or_default()
+    }
+
     /// Saves the contents of the builder to the package compute properties data structure.
     /// If a main block ID is provided, it returns the applications generator set representing the block.
     pub fn save_to_package_compute_properties(
@@ -210,7 +220,7 @@ impl GeneratorSetsBuilder {
                     .for_each(|value_kind| {
                         array_param_application
                             .static_content_dynamic_size
-                            .aggregate_value_kind(*value_kind);
+                            .aggregate_value_kind(value_kind.clone());
                     });
                 array_compute_properties
                     .dynamic_content_static_size
@@ -219,7 +229,7 @@ impl GeneratorSetsBuilder {
                     .for_each(|value_kind| {
                         array_param_application
                             .dynamic_content_static_size
-                            .aggregate_value_kind(*value_kind);
+                            .aggregate_value_kind(value_kind.clone());
                     });
                 array_compute_properties
                     .dynamic_content_dynamic_size
@@ -228,7 +238,7 @@ impl GeneratorSetsBuilder {
                     .for_each(|value_kind| {
                         array_param_application
                             .dynamic_content_dynamic_size
-                            .aggregate_value_kind(*value_kind);
+                            .aggregate_value_kind(value_kind.clone());
                     });
             }
             crate::ParamApplication::Element(element_param_application) => {
@@ -241,7 +251,7 @@ impl GeneratorSetsBuilder {
                     .value_kind
                     .iter()
                     .for_each(|value_kind| {
-                        element_param_application.aggregate_value_kind(*value_kind);
+                        element_param_application.aggregate_value_kind(value_kind.clone());
                     });
             }
         };
@@ -334,6 +344,7 @@ impl GeneratorSetsBuilder {
             let application_generator_set = ApplicationGeneratorSet {
                 inherent: block_inherent_compute_kind,
                 dynamic_param_applications: block_dynamic_param_applications,
+                from_cycle_assumption: false,
             };
             package_compute_properties
                 .blocks
@@ -356,6 +367,7 @@ impl GeneratorSetsBuilder {
             let application_generator_set = ApplicationGeneratorSet {
                 inherent: stmt_inherent_compute_kind,
                 dynamic_param_applications: stmt_dynamic_param_applications,
+                from_cycle_assumption: false,
             };
             package_compute_properties
                 .stmts
@@ -378,6 +390,7 @@ impl GeneratorSetsBuilder {
             let application_generator_set = ApplicationGeneratorSet {
                 inherent: expr_inherent_compute_kind,
                 dynamic_param_applications: expr_dynamic_param_applications,
+                from_cycle_assumption: false,
             };
             package_compute_properties
                 .exprs
@@ -393,6 +406,8 @@ pub struct ApplicationInstance {
     pub locals_map: LocalsComputeKindMap,
     /// The currently active dynamic scopes in the application instance.
     pub active_dynamic_scopes: Vec<ExprId>,
+    /// The maximum number of dynamic scopes that have been simultaneously active in the application instance.
+    pub max_dynamic_scope_depth: usize,
     /// The return expressions throughout the application instance.
     /// The first ID in the tuple represents the return expression itself.
     /// The second ID in the tuple represents the returned value expression.
@@ -447,11 +462,18 @@ impl ApplicationInstance {
         self.stmts.insert(id, value);
     }
 
+    pub fn push_dynamic_scope(&mut self, id: ExprId) {
+        self.active_dynamic_scopes.push(id);
+        self.max_dynamic_scope_depth = self
+            .max_dynamic_scope_depth
+            .max(self.active_dynamic_scopes.len());
+    }
+
     fn new(
         input_params: &Vec<InputParam>,
         controls: Option<&Local>,
         return_type: &Ty,
-        dynamic_param: Option<(InputParamIndex, ValueKind)>,
+        dynamic_param: Option<(InputParamIndex, &ValueKind)>,
     ) -> Self {
         // Initialize the locals map with the specialization controls (if any).
         let mut locals_map = LocalsComputeKindMap::default();
@@ -460,14 +482,12 @@ impl ApplicationInstance {
             // no runtime features here.
             let compute_kind = ComputeKind::Quantum(QuantumProperties {
                 runtime_features: RuntimeFeatureFlags::empty(),
+                extended_features: ExtendedFeatures::empty(),
                 value_kind: ValueKind::Array(RuntimeKind::Static, RuntimeKind::Static),
             });
             locals_map.insert(
                 controls.var,
-                LocalComputeKind {
-                    local: controls.clone(),
-                    compute_kind,
-                },
+                LocalComputeKind::new(controls.clone(), compute_kind),
             );
         }
 
@@ -483,22 +503,18 @@ impl ApplicationInstance {
                 if input_param_index == dynamic_param_index {
                     compute_kind = ComputeKind::Quantum(QuantumProperties {
                         runtime_features: RuntimeFeatureFlags::empty(),
-                        value_kind: dynamic_param_value_kind,
+                        extended_features: ExtendedFeatures::empty(),
+                        value_kind: dynamic_param_value_kind.clone(),
                     });
                 }
             }
 
-            locals_map.insert(
-                local_var_id,
-                LocalComputeKind {
-                    local,
-                    compute_kind,
-                },
-            );
+            locals_map.insert(local_var_id, LocalComputeKind::new(local, compute_kind));
         }
         Self {
             locals_map,
             active_dynamic_scopes: Vec::new(),
+            max_dynamic_scope_depth: 0,
             return_expressions: Vec::new(),
             return_type: return_type.clone(),
             blocks: FxHashMap::default(),
@@ -532,7 +548,7 @@ impl ApplicationInstance {
                     else {
                         panic!("returned value expression is expected to be quantum");
                     };
-                    returned_value_quantum_properties.value_kind
+                    returned_value_quantum_properties.value_kind.clone()
                 };
                 value_kinds.push(return_value_kind);
             }
@@ -543,15 +559,11 @@ impl ApplicationInstance {
         let value_kind = if value_kinds.is_empty() {
             None
         } else {
-            let initial_value_kind = if let Ty::Array(_) = self.return_type {
-                ValueKind::Array(RuntimeKind::Static, RuntimeKind::Static)
-            } else {
-                ValueKind::Element(RuntimeKind::Static)
-            };
-            let value_kind = value_kinds.iter().fold(
+            let initial_value_kind = ValueKind::new_static_from_type(&self.return_type);
+            let value_kind = value_kinds.into_iter().fold(
                 initial_value_kind,
                 |aggregated_value_kind, return_value_kind| {
-                    aggregated_value_kind.aggregate(*return_value_kind)
+                    aggregated_value_kind.aggregate(return_value_kind)
                 },
             );
             Some(value_kind)
@@ -583,7 +595,24 @@ impl LocalsComputeKindMap {
             .0
             .get_mut(local_var_id)
             .expect("local compute kind does not exist");
-        local_compute_kind.compute_kind = local_compute_kind.compute_kind.aggregate(delta);
+        local_compute_kind.compute_kind = local_compute_kind.compute_kind.clone().aggregate(delta);
+    }
+
+    pub fn aggregate_field_compute_kind(
+        &mut self,
+        local_var_id: LocalVarId,
+        path: Vec<usize>,
+        delta: ComputeKind,
+    ) {
+        let local_compute_kind = self
+            .0
+            .get_mut(local_var_id)
+            .expect("local compute kind does not exist");
+        let field_compute_kind = local_compute_kind
+            .fields
+            .entry(path)
+            .or_insert(ComputeKind::Classical);
+        *field_compute_kind = field_compute_kind.clone().aggregate(delta);
     }
 
     pub fn find_local_compute_kind(&self, local_var_id: LocalVarId) -> Option<&LocalComputeKind> {
@@ -604,6 +633,50 @@ impl LocalsComputeKindMap {
 pub struct LocalComputeKind {
     pub local: Local,
     pub compute_kind: ComputeKind,
+    /// The compute kind of the individual fields that have been assigned to, keyed by their field path.
+    pub fields: FxHashMap<Vec<usize>, ComputeKind>,
+}
+
+impl LocalComputeKind {
+    pub fn new(local: Local, compute_kind: ComputeKind) -> Self {
+        Self {
+            local,
+            compute_kind,
+            fields: FxHashMap::default(),
+        }
+    }
+
+    /// Gets the compute kind of the local as a whole, which includes the updates made to its individual fields.
+    pub fn whole_compute_kind(&self) -> ComputeKind {
+        let mut compute_kind = self.compute_kind.clone();
+        for field_compute_kind in self.fields.values() {
+            compute_kind = aggregate_field_update(compute_kind, field_compute_kind, &self.local.ty);
+        }
+        compute_kind
+    }
+
+    /// Gets the compute kind of the field with the given path, which only includes the updates made to the field
+    /// itself, to any of its sub-fields or to any of the fields that contain it.
+    pub fn field_compute_kind(&self, path: &[usize], field_ty: &Ty) -> ComputeKind {
+        let mut compute_kind = ComputeKind::Classical;
+        compute_kind = aggregate_field_update(compute_kind, &self.compute_kind, field_ty);
+        for (field_path, field_compute_kind) in &self.fields {
+            if field_path.starts_with(path) || path.starts_with(field_path) {
+                compute_kind = aggregate_field_update(compute_kind, field_compute_kind, field_ty);
+            }
+        }
+        compute_kind
+    }
+}
+
+/// Aggregates the compute kind of a field update into the compute kind of a value of the given type.
+fn aggregate_field_update(compute_kind: ComputeKind, update: &ComputeKind, ty: &Ty) -> ComputeKind {
+    let mut compute_kind =
+        compute_kind.aggregate_runtime_features(update, &ValueKind::new_static_from_type(ty));
+    if update.is_dynamic() {
+        compute_kind.aggregate_value_kind(ValueKind::new_dynamic_from_type(ty));
+    }
+    compute_kind
 }
 
 #[derive(Clone, Copy)]
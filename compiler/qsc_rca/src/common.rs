@@ -5,10 +5,12 @@ use indenter::Indented;
 use qsc_data_structures::{functors::FunctorApp, index_map::IndexMap};
 use qsc_fir::{
     fir::{
-        CallableDecl, ExprId, ExprKind, Functor, ItemId, LocalItemId, LocalVarId, PackageId,
-        PackageLookup, Pat, PatId, PatKind, Res, StoreItemId, UnOp,
+        Block, BlockId, CallableDecl, Expr, ExprId, ExprKind, Functor, ItemId, Lit, LocalItemId,
+        LocalVarId, PackageId, PackageLookup, Pat, PatId, PatKind, Res, SpecDecl, SpecImpl, Stmt,
+        StmtId, StoreItemId, UnOp,
     },
     ty::{FunctorSetValue, Ty},
+    visit::{walk_expr, Visitor},
 };
 use rustc_hash::FxHashMap;
 use std::fmt::{Debug, Formatter};
@@ -38,6 +40,8 @@ pub struct InputParam {
     pub var: Option<LocalVarId>,
 }
 
+/// Derives the input parameters of a callable from its input pattern only, so bindings synthesized in the body (e.g.
+/// when desugaring defaulted arguments) never count as parameters even if they shadow one.
 pub fn derive_callable_input_params(
     callable: &CallableDecl,
     pats: &IndexMap<PatId, Pat>,
@@ -140,7 +144,8 @@ impl From<(LocalItemId, FunctorSetValue)> for LocalSpecId {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+/// The ID of a callable specialization in a package store.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct GlobalSpecId {
     pub callable: StoreItemId,
     pub functor_set_value: FunctorSetValue,
@@ -274,6 +279,13 @@ pub fn try_resolve_callee(
     // global callables or locals that eventually resolve to global callables.
     let expr = package.get_expr(expr_id);
     match &expr.kind {
+        ExprKind::Index(array_expr_id, index_expr_id) => try_resolve_index_callee(
+            *array_expr_id,
+            *index_expr_id,
+            package_id,
+            package,
+            locals_map,
+        ),
         ExprKind::UnOp(operator, operand_expr_id) => {
             try_resolve_un_op_callee(*operator, *operand_expr_id, package_id, package, locals_map)
         }
@@ -289,6 +301,44 @@ pub fn try_resolve_callee(
     }
 }
 
+fn try_resolve_index_callee(
+    array_expr_id: ExprId,
+    index_expr_id: ExprId,
+    package_id: PackageId,
+    package: &impl PackageLookup,
+    locals_map: &impl LocalsLookup,
+) -> Option<Callee> {
+    // This is a best effort attempt to resolve a callee that is an element of an array literal accessed through a
+    // literal index, following immutable locals bound to the array.
+    let ExprKind::Lit(Lit::Int(index)) = package.get_expr(index_expr_id).kind else {
+        return None;
+    };
+    let elements = try_resolve_array_elements(array_expr_id, package, locals_map)?;
+    let element_expr_id = elements.get(usize::try_from(index).ok()?)?;
+    try_resolve_callee(*element_expr_id, package_id, package, locals_map)
+}
+
+fn try_resolve_array_elements<'a>(
+    expr_id: ExprId,
+    package: &'a impl PackageLookup,
+    locals_map: &impl LocalsLookup,
+) -> Option<&'a [ExprId]> {
+    match &package.get_expr(expr_id).kind {
+        ExprKind::Array(elements) => Some(elements.as_slice()),
+        ExprKind::Var(Res::Local(local_var_id), _) => {
+            locals_map
+                .find(*local_var_id)
+                .and_then(|local| match local.kind {
+                    LocalKind::Immutable(expr_id) => {
+                        try_resolve_array_elements(expr_id, package, locals_map)
+                    }
+                    _ => None,
+                })
+        }
+        _ => None,
+    }
+}
+
 fn resolve_item_callee(call_package_id: PackageId, item_id: ItemId) -> Callee {
     let package_id = item_id.package.unwrap_or(call_package_id);
     Callee {
@@ -344,6 +394,105 @@ fn try_resolve_un_op_callee(
     })
 }
 
+/// Gets the declaration of the specialization that corresponds to the provided functor set value.
+/// An operation that measures cannot be adjointed, so when one is erroneously declared as adjointable its adjoint
+/// specializations might be missing. In that case, the body is used instead of the adjoint and the controlled
+/// specialization (or the body) instead of the controlled adjoint, so the analysis surfaces the runtime features they use
+/// and lets downstream consumers reject the program.
+pub fn get_spec_decl(spec_impl: &SpecImpl, functor_set_value: FunctorSetValue) -> &SpecDecl {
+    match functor_set_value {
+        FunctorSetValue::Empty => &spec_impl.body,
+        FunctorSetValue::Adj => spec_impl.adj.as_ref().unwrap_or(&spec_impl.body),
+        FunctorSetValue::Ctl => spec_impl
+            .ctl
+            .as_ref()
+            .expect("ctl specialization should exist"),
+        FunctorSetValue::CtlAdj => spec_impl
+            .ctl_adj
+            .as_ref()
+            .or(spec_impl.ctl.as_ref())
+            .unwrap_or(&spec_impl.body),
+    }
+}
+
+/// Whether a loop possibly never terminates, which is heuristically assumed when its condition is the literal `true`
+/// and its body has no `return` or `fail` expression to exit it.
+pub fn is_possibly_non_terminating_loop(
+    condition_expr_id: ExprId,
+    block_id: BlockId,
+    package: &impl PackageLookup,
+) -> bool {
+    matches!(
+        package.get_expr(condition_expr_id).kind,
+        ExprKind::Lit(Lit::Bool(true))
+    ) && !block_contains_expr(block_id, package, |expr| {
+        matches!(expr.kind, ExprKind::Return(_) | ExprKind::Fail(_))
+    })
+}
+
+/// Whether a block contains a loop that possibly never terminates.
+pub fn contains_possibly_non_terminating_loop(
+    block_id: BlockId,
+    package: &impl PackageLookup,
+) -> bool {
+    block_contains_expr(block_id, package, |expr| match expr.kind {
+        ExprKind::While(condition_expr_id, loop_block_id) => {
+            is_possibly_non_terminating_loop(condition_expr_id, loop_block_id, package)
+        }
+        _ => false,
+    })
+}
+
+fn block_contains_expr<'a, P: PackageLookup>(
+    block_id: BlockId,
+    package: &'a P,
+    predicate: impl Fn(&'a Expr) -> bool,
+) -> bool {
+    let mut finder = ExprFinder {
+        package,
+        predicate,
+        found: false,
+    };
+    finder.visit_block(block_id);
+    finder.found
+}
+
+/// Finds whether any expression visited satisfies a predicate.
+struct ExprFinder<'a, P, F> {
+    package: &'a P,
+    predicate: F,
+    found: bool,
+}
+
+impl<'a, P: PackageLookup, F: Fn(&'a Expr) -> bool> Visitor<'a> for ExprFinder<'a, P, F> {
+    fn get_block(&self, id: BlockId) -> &'a Block {
+        self.package.get_block(id)
+    }
+
+    fn get_expr(&self, id: ExprId) -> &'a Expr {
+        self.package.get_expr(id)
+    }
+
+    fn get_pat(&self, id: PatId) -> &'a Pat {
+        self.package.get_pat(id)
+    }
+
+    fn get_stmt(&self, id: StmtId) -> &'a Stmt {
+        self.package.get_stmt(id)
+    }
+
+    fn visit_expr(&mut self, expr_id: ExprId) {
+        if self.found {
+            return;
+        }
+        if (self.predicate)(self.get_expr(expr_id)) {
+            self.found = true;
+            return;
+        }
+        walk_expr(self, expr_id);
+    }
+}
+
 pub fn set_indentation<'a, 'b>(
     indent: Indented<'a, Formatter<'b>>,
     level: usize,
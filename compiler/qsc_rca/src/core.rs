@@ -4,48 +4,83 @@
 use crate::{
     applications::{ApplicationInstance, GeneratorSetsBuilder, LocalComputeKind},
     common::{
-        derive_callable_input_params, try_resolve_callee, Callee, FunctorAppExt, GlobalSpecId,
-        InputParam, Local, LocalKind, TyExt,
+        derive_callable_input_params, get_spec_decl, is_possibly_non_terminating_loop,
+        try_resolve_callee, Callee, FunctorAppExt, GlobalSpecId, InputParam, Local, LocalKind,
+        TyExt,
     },
     scaffolding::{InternalItemComputeProperties, InternalPackageStoreComputeProperties},
     ApplicationGeneratorSet, ArrayParamApplication, ComputeKind, ComputePropertiesLookup,
-    ParamApplication, QuantumProperties, RuntimeFeatureFlags, RuntimeKind, ValueKind,
+    DebugOnlyIntrinsicsHook, ExtendedFeatures, IntrinsicFeaturesHook, ParamApplication,
+    QuantumProperties, RuntimeFeatureFlags, RuntimeKind, ValueKind,
 };
 use qsc_data_structures::{functors::FunctorApp, index_map::IndexMap};
 use qsc_fir::{
     fir::{
-        Block, BlockId, CallableDecl, CallableImpl, CallableKind, Expr, ExprId, ExprKind, Global,
-        Ident, Item, ItemKind, Mutability, Package, PackageId, PackageLookup, PackageStore,
-        PackageStoreLookup, Pat, PatId, PatKind, Res, SpecDecl, SpecImpl, Stmt, StmtId, StmtKind,
-        StoreExprId, StoreItemId, StorePatId, StringComponent,
+        BinOp, Block, BlockId, CallableDecl, CallableImpl, CallableKind, Expr, ExprId, ExprKind,
+        Field, Global, Ident, Item, ItemKind, Lit, LocalItemId, LocalVarId, Mutability, Package,
+        PackageId, PackageLookup, PackageStore, PackageStoreLookup, Pat, PatId, PatKind, Pauli,
+        Res, SpecDecl, SpecImpl, Stmt, StmtId, StmtKind, StoreExprId, StoreItemId, StorePatId,
+        StringComponent,
     },
     ty::{Arrow, FunctorSetValue, Prim, Ty},
     visit::Visitor,
 };
+use rustc_hash::FxHashSet;
 
 pub struct Analyzer<'a> {
     package_store: &'a PackageStore,
     package_store_compute_properties: InternalPackageStoreComputeProperties,
     active_contexts: Vec<AnalysisContext>,
+    intrinsic_features_hook: Option<IntrinsicFeaturesHook<'a>>,
+    debug_only_intrinsics_hook: Option<DebugOnlyIntrinsicsHook<'a>>,
+    unresolved_callees: Vec<StoreExprId>,
+    /// The standard library operation that applies a global phase, if it is part of the store.
+    apply_global_phase_id: Option<StoreItemId>,
+    /// The standard library rotation about a Pauli axis, if it is part of the store.
+    r_id: Option<StoreItemId>,
 }
 
 impl<'a> Analyzer<'a> {
     pub fn new(
         package_store: &'a PackageStore,
         package_store_compute_properties: InternalPackageStoreComputeProperties,
+        intrinsic_features_hook: Option<IntrinsicFeaturesHook<'a>>,
+        debug_only_intrinsics_hook: Option<DebugOnlyIntrinsicsHook<'a>>,
     ) -> Self {
         Self {
             package_store,
             package_store_compute_properties,
             active_contexts: Vec::<AnalysisContext>::default(),
+            intrinsic_features_hook,
+            debug_only_intrinsics_hook,
+            unresolved_callees: Vec::new(),
+            apply_global_phase_id: find_intrinsic_callable(package_store, "ApplyGlobalPhase"),
+            r_id: find_intrinsic_callable(package_store, "R"),
         }
     }
 
-    pub fn analyze_all(mut self) -> InternalPackageStoreComputeProperties {
+    /// Analyzes all the packages in the store, also returning the callee expressions that could not be resolved to a
+    /// specific callable.
+    pub fn analyze_all_with_progress(
+        mut self,
+        mut progress: impl FnMut(usize, usize),
+    ) -> (InternalPackageStoreComputeProperties, Vec<StoreExprId>) {
+        let total = self
+            .package_store
+            .iter()
+            .map(|(_, package)| package.items.iter().count())
+            .sum();
+        let mut done = 0;
         for (package_id, package) in self.package_store {
-            self.analyze_package_internal(package_id, package);
+            self.analyze_package_internal(package_id, package, &mut || {
+                done += 1;
+                progress(done, total);
+            });
         }
-        self.package_store_compute_properties
+        (
+            self.package_store_compute_properties,
+            self.unresolved_callees,
+        )
     }
 
     pub fn analyze_package(
@@ -53,7 +88,59 @@ impl<'a> Analyzer<'a> {
         package_id: PackageId,
     ) -> InternalPackageStoreComputeProperties {
         let package = self.package_store.get(package_id);
-        self.analyze_package_internal(package_id, package);
+        self.analyze_package_internal(package_id, package, &mut || {});
+        self.package_store_compute_properties
+    }
+
+    pub fn analyze_expr_standalone(
+        mut self,
+        package_id: PackageId,
+        expr_id: ExprId,
+    ) -> (ComputeKind, InternalPackageStoreComputeProperties) {
+        // Visit the expression within a standalone top-level context, which is discarded afterwards since only the
+        // compute kind of the expression itself is of interest.
+        self.active_contexts
+            .push(AnalysisContext::TopLevel(TopLevelContext::new_standalone(
+                package_id,
+            )));
+        self.visit_expr(expr_id);
+        let compute_kind = self
+            .get_current_application_instance()
+            .get_expr_compute_kind(expr_id)
+            .clone();
+        let top_level_context = self.pop_top_level_context();
+        assert!(top_level_context.package_id == package_id);
+        (compute_kind, self.package_store_compute_properties)
+    }
+
+    pub fn analyze_top_level_stmt(
+        mut self,
+        package_id: PackageId,
+        stmt_id: StmtId,
+    ) -> InternalPackageStoreComputeProperties {
+        // The locals bound by previously analyzed top-level statements are not kept around, so visit those statements
+        // again within the same top-level context to bind them before visiting the new statement. The items of the
+        // package are not visited, so the analysis of any specialization already populated is reused as is.
+        self.active_contexts
+            .push(AnalysisContext::TopLevel(TopLevelContext::new_standalone(
+                package_id,
+            )));
+        for previous_stmt_id in self
+            .top_level_stmts(package_id)
+            .into_iter()
+            .filter(|previous_stmt_id| *previous_stmt_id < stmt_id)
+        {
+            self.visit_stmt(previous_stmt_id);
+        }
+        self.visit_stmt(stmt_id);
+        let top_level_context = self.pop_top_level_context();
+        assert!(top_level_context.package_id == package_id);
+
+        // Save the analysis of the statement to the corresponding package compute properties.
+        let package_compute_properties = self.package_store_compute_properties.get_mut(package_id);
+        top_level_context
+            .builder
+            .save_to_package_compute_properties(package_compute_properties, None);
         self.package_store_compute_properties
     }
 
@@ -68,7 +155,7 @@ impl<'a> Analyzer<'a> {
             let application_instance = self.get_current_application_instance();
             let expr_compute_kind = application_instance.get_expr_compute_kind(*expr_id);
             compute_kind =
-                compute_kind.aggregate_runtime_features(*expr_compute_kind, default_value_kind);
+                compute_kind.aggregate_runtime_features(expr_compute_kind, &default_value_kind);
             has_dynamic_content |= expr_compute_kind.is_dynamic();
         }
 
@@ -99,14 +186,18 @@ impl<'a> Analyzer<'a> {
         // The runtime features the array repeat expression is determined by aggregating the runtime features of both
         // the size and value expressions.
         let application_instance = self.get_current_application_instance();
-        let size_expr_compute_kind = *application_instance.get_expr_compute_kind(size_expr_id);
-        let value_expr_compute_kind = *application_instance.get_expr_compute_kind(value_expr_id);
+        let size_expr_compute_kind = application_instance
+            .get_expr_compute_kind(size_expr_id)
+            .clone();
+        let value_expr_compute_kind = application_instance
+            .get_expr_compute_kind(value_expr_id)
+            .clone();
         let default_value_kind = ValueKind::Array(RuntimeKind::Static, RuntimeKind::Static);
         let mut compute_kind = ComputeKind::Classical;
         compute_kind =
-            compute_kind.aggregate_runtime_features(size_expr_compute_kind, default_value_kind);
+            compute_kind.aggregate_runtime_features(&size_expr_compute_kind, &default_value_kind);
         compute_kind =
-            compute_kind.aggregate_runtime_features(value_expr_compute_kind, default_value_kind);
+            compute_kind.aggregate_runtime_features(&value_expr_compute_kind, &default_value_kind);
 
         if let ComputeKind::Quantum(quantum_properties) = &mut compute_kind {
             // If the array is dynamic, it requires an additional runtime feature.
@@ -157,7 +248,92 @@ impl<'a> Analyzer<'a> {
         // The compute kind of an assign expression is determined by the runtime features of the updated compute kind
         // associated to the local variable.
         compute_kind =
-            compute_kind.aggregate_runtime_features(updated_compute_kind, default_value_kind);
+            compute_kind.aggregate_runtime_features(&updated_compute_kind, &default_value_kind);
+        compute_kind
+    }
+
+    fn analyze_expr_assign_op(
+        &mut self,
+        op: BinOp,
+        assignee_expr_id: ExprId,
+        value_expr_id: ExprId,
+    ) -> ComputeKind {
+        let mut compute_kind = self.analyze_expr_assign(assignee_expr_id, value_expr_id);
+
+        // An arithmetic compound assignment computes a numeric value out of its operands, so when any of them is
+        // dynamic it is tracked the same way as the equivalent binary operator expression.
+        let assignee_expr = self.get_expr(assignee_expr_id);
+        if !is_numeric_bin_op(op, &assignee_expr.ty) {
+            return compute_kind;
+        }
+        let application_instance = self.get_current_application_instance();
+        let is_dynamic = application_instance
+            .get_expr_compute_kind(assignee_expr_id)
+            .is_dynamic()
+            || application_instance
+                .get_expr_compute_kind(value_expr_id)
+                .is_dynamic();
+        if is_dynamic {
+            let default_value_kind = ValueKind::Element(RuntimeKind::Static);
+            let arithmetic_compute_kind = ComputeKind::new_with_runtime_features(
+                derive_runtime_features_for_dynamic_arithmetic(&assignee_expr.ty),
+                default_value_kind.clone(),
+            );
+            if let ExprKind::Var(Res::Local(local_var_id), _) = &assignee_expr.kind {
+                if self.is_tracked_local(*local_var_id) {
+                    let application_instance = self.get_current_application_instance_mut();
+                    application_instance
+                        .locals_map
+                        .aggregate_compute_kind(*local_var_id, arithmetic_compute_kind.clone());
+                }
+            }
+            compute_kind = compute_kind
+                .aggregate_runtime_features(&arithmetic_compute_kind, &default_value_kind);
+        }
+        compute_kind
+    }
+
+    fn analyze_expr_assign_field(
+        &mut self,
+        record_expr_id: ExprId,
+        field: &Field,
+        value_expr_id: ExprId,
+    ) -> ComputeKind {
+        // Only field assignments to local variables can be tracked at the field level, anything else is handled as an
+        // assignment to the whole record.
+        let record_expr = self.get_expr(record_expr_id);
+        let (ExprKind::Var(Res::Local(local_var_id), _), Field::Path(field_path)) =
+            (&record_expr.kind, field)
+        else {
+            return self.analyze_expr_assign(record_expr_id, value_expr_id);
+        };
+        if !self.is_tracked_local(*local_var_id) {
+            return self.analyze_expr_assign(record_expr_id, value_expr_id);
+        }
+
+        // Visit the record and value expressions to determine their compute kind.
+        self.visit_expr(record_expr_id);
+        self.visit_expr(value_expr_id);
+
+        // Update the compute kind of the assigned field with the compute kind of the value expression, leaving the rest
+        // of the fields of the local variable untouched.
+        let value_expr = self.get_expr(value_expr_id);
+        let updated_compute_kind = self.derive_updated_compute_kind(value_expr_id, &value_expr.ty);
+        let application_instance = self.get_current_application_instance_mut();
+        application_instance
+            .locals_map
+            .aggregate_field_compute_kind(
+                *local_var_id,
+                field_path.indices.clone(),
+                updated_compute_kind.clone(),
+            );
+
+        // The compute kind of an assign field expression is determined by the runtime features of the updated compute
+        // kind associated to the field.
+        let default_value_kind = ValueKind::Element(RuntimeKind::Static);
+        let mut compute_kind = ComputeKind::Classical;
+        compute_kind =
+            compute_kind.aggregate_runtime_features(&updated_compute_kind, &default_value_kind);
         compute_kind
     }
 
@@ -175,12 +351,13 @@ impl<'a> Analyzer<'a> {
         // Since this is an assignment, the compute kind of the local variable (array var expression) needs to be updated.
         // The compute kind of the update is determined by the runtime features of the replacement value expression.
         let application_instance = self.get_current_application_instance();
-        let replacement_value_compute_kind =
-            *application_instance.get_expr_compute_kind(replacement_value_expr_id);
+        let replacement_value_compute_kind = application_instance
+            .get_expr_compute_kind(replacement_value_expr_id)
+            .clone();
         let default_value_kind = ValueKind::Array(RuntimeKind::Static, RuntimeKind::Static);
         let mut updated_compute_kind = ComputeKind::Classical;
         updated_compute_kind = updated_compute_kind
-            .aggregate_runtime_features(replacement_value_compute_kind, default_value_kind);
+            .aggregate_runtime_features(&replacement_value_compute_kind, &default_value_kind);
 
         // If the replacement value expression is dynamic, the runtime features and value kind of the update have to
         // take this into account.
@@ -202,31 +379,35 @@ impl<'a> Analyzer<'a> {
         let ExprKind::Var(Res::Local(local_var_id), _) = &array_var_expr.kind else {
             panic!("LHS expression should be a local");
         };
-        let application_instance = self.get_current_application_instance_mut();
-        application_instance
-            .locals_map
-            .aggregate_compute_kind(*local_var_id, updated_compute_kind);
+        if self.is_tracked_local(*local_var_id) {
+            self.get_current_application_instance_mut()
+                .locals_map
+                .aggregate_compute_kind(*local_var_id, updated_compute_kind);
+        }
+        let application_instance = self.get_current_application_instance();
 
         // The compute kind of this expression is determined by aggregating the runtime features of the index and
         // replacement expressions.
         // We do not care about the value kind for this kind of expression because it is an assignment, but we still
         // need a default one.
         let default_value_kind = ValueKind::Element(RuntimeKind::Static);
-        let index_compute_kind = *application_instance.get_expr_compute_kind(index_expr_id);
+        let index_compute_kind = application_instance
+            .get_expr_compute_kind(index_expr_id)
+            .clone();
         let mut compute_kind = ComputeKind::Classical;
         compute_kind =
-            compute_kind.aggregate_runtime_features(index_compute_kind, default_value_kind);
+            compute_kind.aggregate_runtime_features(&index_compute_kind, &default_value_kind);
         compute_kind = compute_kind
-            .aggregate_runtime_features(replacement_value_compute_kind, default_value_kind);
+            .aggregate_runtime_features(&replacement_value_compute_kind, &default_value_kind);
 
         // Finally, if the index expression is dynamic, we aggregate an additional runtime feature.
         if index_compute_kind.is_dynamic() {
             compute_kind = compute_kind.aggregate_runtime_features(
-                ComputeKind::new_with_runtime_features(
+                &ComputeKind::new_with_runtime_features(
                     RuntimeFeatureFlags::UseOfDynamicIndex,
-                    default_value_kind,
+                    default_value_kind.clone(),
                 ),
-                default_value_kind,
+                &default_value_kind,
             );
         }
         compute_kind
@@ -234,6 +415,7 @@ impl<'a> Analyzer<'a> {
 
     fn analyze_expr_bin_op(
         &mut self,
+        op: BinOp,
         lhs_expr_id: ExprId,
         rhs_expr_id: ExprId,
         expr_type: &Ty,
@@ -244,8 +426,12 @@ impl<'a> Analyzer<'a> {
 
         // The compute kind of a binary operator expression is the aggregation of its LHS and RHS expressions.
         let application_instance = self.get_current_application_instance();
-        let lhs_compute_kind = *application_instance.get_expr_compute_kind(lhs_expr_id);
-        let rhs_compute_kind = *application_instance.get_expr_compute_kind(rhs_expr_id);
+        let lhs_compute_kind = application_instance
+            .get_expr_compute_kind(lhs_expr_id)
+            .clone();
+        let rhs_compute_kind = application_instance
+            .get_expr_compute_kind(rhs_expr_id)
+            .clone();
         let mut compute_kind = ComputeKind::Classical;
         compute_kind = compute_kind.aggregate(lhs_compute_kind);
         compute_kind = compute_kind.aggregate(rhs_compute_kind);
@@ -258,7 +444,13 @@ impl<'a> Analyzer<'a> {
             };
 
             quantum_properties.runtime_features |=
-                derive_runtime_features_for_value_kind_associated_to_type(value_kind, expr_type);
+                derive_runtime_features_for_value_kind_associated_to_type(&value_kind, expr_type);
+
+            // Computing a numeric value out of a dynamic operand is tracked separately from branching on it.
+            if value_kind.is_dynamic() && is_numeric_bin_op(op, expr_type) {
+                quantum_properties.runtime_features |=
+                    derive_runtime_features_for_dynamic_arithmetic(expr_type);
+            }
         }
 
         compute_kind
@@ -270,7 +462,9 @@ impl<'a> Analyzer<'a> {
 
         // The compute kind of a block expression is the same as the compute kind of the block.
         let application_instance = self.get_current_application_instance();
-        *application_instance.get_block_compute_kind(block_id)
+        application_instance
+            .get_block_compute_kind(block_id)
+            .clone()
     }
 
     fn analyze_expr_call(
@@ -285,15 +479,29 @@ impl<'a> Analyzer<'a> {
 
         // The compute kind of this expression depends on whether the callee expression is dynamic.
         let application_instance = self.get_current_application_instance();
-        let callee_expr_compute_kind = *application_instance.get_expr_compute_kind(callee_expr_id);
+        let callee_expr_compute_kind = application_instance
+            .get_expr_compute_kind(callee_expr_id)
+            .clone();
         let mut compute_kind = if callee_expr_compute_kind.is_dynamic() {
             // The value kind of a call expression with an dynamic callee is dynamic but its specific variant depends
             // on the expression's type.
             let value_kind = ValueKind::new_dynamic_from_type(expr_type);
-            ComputeKind::Quantum(QuantumProperties {
+            let mut compute_kind = ComputeKind::Quantum(QuantumProperties {
                 runtime_features: RuntimeFeatureFlags::CallToDynamicCallee,
-                value_kind,
-            })
+                extended_features: ExtendedFeatures::empty(),
+                value_kind: value_kind.clone(),
+            });
+
+            // A closure defers the effects of the callable it is lifted to (including those on captured qubits) until
+            // it is applied, so if the callee resolves to a closure, also use the runtime features of its application.
+            if let Some((captures, closure_item)) = self.try_resolve_closure_callee(callee_expr_id)
+            {
+                let closure_application_compute_kind =
+                    self.analyze_closure_application(captures, closure_item, args_expr_id);
+                compute_kind = compute_kind
+                    .aggregate_runtime_features(&closure_application_compute_kind, &value_kind);
+            }
+            compute_kind
         } else {
             self.analyze_expr_call_with_static_callee(callee_expr_id, args_expr_id, expr_type)
         };
@@ -304,11 +512,11 @@ impl<'a> Analyzer<'a> {
         if !application_instance.active_dynamic_scopes.is_empty() {
             // Any call that happens within a dynamic scope uses the forward branching runtime feature.
             compute_kind = compute_kind.aggregate_runtime_features(
-                ComputeKind::new_with_runtime_features(
+                &ComputeKind::new_with_runtime_features(
                     RuntimeFeatureFlags::ForwardBranchingOnDynamicValue,
-                    default_value_kind,
+                    default_value_kind.clone(),
                 ),
-                default_value_kind,
+                &default_value_kind,
             );
 
             // If the call expression type is either a result or a qubit, it uses dynamic allocation runtime features.
@@ -322,11 +530,22 @@ impl<'a> Analyzer<'a> {
 
             if let Ty::Prim(Prim::Result) = expr_type {
                 compute_kind = compute_kind.aggregate_runtime_features(
-                    ComputeKind::new_with_runtime_features(
+                    &ComputeKind::new_with_runtime_features(
                         RuntimeFeatureFlags::DynamicResultAllocation,
-                        default_value_kind,
+                        default_value_kind.clone(),
                     ),
-                    default_value_kind,
+                    &default_value_kind,
+                );
+            }
+
+            // If the call releases a qubit, it uses the dynamic qubit release runtime feature.
+            if self.is_qubit_release_call(callee_expr_id) {
+                compute_kind = compute_kind.aggregate_runtime_features(
+                    &ComputeKind::new_with_runtime_features(
+                        RuntimeFeatureFlags::DynamicQubitRelease,
+                        default_value_kind.clone(),
+                    ),
+                    &default_value_kind,
                 );
             }
         }
@@ -337,19 +556,72 @@ impl<'a> Analyzer<'a> {
                 panic!("expected quantum variant of Compute Kind");
             };
             quantum_properties.runtime_features |=
-                derive_runtime_features_for_value_kind_associated_to_type(value_kind, expr_type);
+                derive_runtime_features_for_value_kind_associated_to_type(&value_kind, expr_type);
         }
 
         // Aggregate the runtime features of the callee and arguments expressions.
-        let callee_expr_compute_kind = *application_instance.get_expr_compute_kind(callee_expr_id);
-        let args_expr_compute_kind = *application_instance.get_expr_compute_kind(args_expr_id);
+        let callee_expr_compute_kind = application_instance
+            .get_expr_compute_kind(callee_expr_id)
+            .clone();
+        let args_expr_compute_kind = application_instance
+            .get_expr_compute_kind(args_expr_id)
+            .clone();
         compute_kind =
-            compute_kind.aggregate_runtime_features(callee_expr_compute_kind, default_value_kind);
+            compute_kind.aggregate_runtime_features(&callee_expr_compute_kind, &default_value_kind);
         compute_kind =
-            compute_kind.aggregate_runtime_features(args_expr_compute_kind, default_value_kind);
+            compute_kind.aggregate_runtime_features(&args_expr_compute_kind, &default_value_kind);
         compute_kind
     }
 
+    fn analyze_closure_application(
+        &mut self,
+        captures: &[LocalVarId],
+        closure_item: LocalItemId,
+        args_expr_id: ExprId,
+    ) -> ComputeKind {
+        let package_id = self.get_current_package_id();
+        let package = self.package_store.get(package_id);
+        let ItemKind::Callable(callable_decl) = &package.get_item(closure_item).kind else {
+            panic!("closure item must be a callable");
+        };
+
+        // Closures are lifted to callables that only have a body specialization.
+        let closure_spec_id = GlobalSpecId::from((
+            StoreItemId::from((package_id, closure_item)),
+            FunctorSetValue::Empty,
+        ));
+        self.analyze_spec(closure_spec_id, callable_decl);
+
+        // The captured locals are the leading input parameters of the lifted callable, so their value kinds come from
+        // the locals map. The rest of the parameters are conservatively considered dynamic if the arguments are.
+        let input_params = derive_callable_input_params(callable_decl, &package.pats);
+        let application_instance = self.get_current_application_instance();
+        let args_are_dynamic = application_instance
+            .get_expr_compute_kind(args_expr_id)
+            .is_dynamic();
+        let arg_value_kinds = input_params
+            .iter()
+            .map(|param| {
+                let static_value_kind = ValueKind::new_static_from_type(&param.ty);
+                match captures.get(usize::from(param.index)) {
+                    Some(captured_var_id) => application_instance
+                        .locals_map
+                        .find_local_compute_kind(*captured_var_id)
+                        .map_or(static_value_kind.clone(), |local_compute_kind| {
+                            local_compute_kind
+                                .whole_compute_kind()
+                                .value_kind_or_default(static_value_kind)
+                        }),
+                    None if args_are_dynamic => ValueKind::new_dynamic_from_type(&param.ty),
+                    None => static_value_kind,
+                }
+            })
+            .collect::<Vec<_>>();
+        self.package_store_compute_properties
+            .get_spec(closure_spec_id)
+            .apply(&arg_value_kinds)
+    }
+
     fn analyze_expr_call_with_spec_callee(
         &mut self,
         callee: &Callee,
@@ -379,21 +651,27 @@ impl<'a> Analyzer<'a> {
             args_input_id,
             self.package_store,
         );
+
+        // Operations that only apply a global phase have no observable effect so their application is classical.
+        if self.is_global_phase_only_application(callee, &arg_exprs) {
+            return ComputeKind::Classical;
+        }
+
         let application_instance = self.get_current_application_instance();
 
         // Derive the compute kind based on the value kind of the arguments.
         let arg_value_kinds = self.derive_arg_value_kinds(&arg_exprs);
-        let mut compute_kind =
-            application_generator_set.generate_application_compute_kind(&arg_value_kinds);
+        let mut compute_kind = application_generator_set.apply(&arg_value_kinds);
 
         // Aggregate the runtime features of the qubit controls expressions.
         let mut has_dynamic_controls = false;
         let default_value_kind = ValueKind::new_static_from_type(&callable_decl.output);
         for control_expr in args_controls {
-            let control_expr_compute_kind =
-                *application_instance.get_expr_compute_kind(control_expr);
+            let control_expr_compute_kind = application_instance
+                .get_expr_compute_kind(control_expr)
+                .clone();
             compute_kind = compute_kind
-                .aggregate_runtime_features(control_expr_compute_kind, default_value_kind);
+                .aggregate_runtime_features(&control_expr_compute_kind, &default_value_kind);
             has_dynamic_controls |= control_expr_compute_kind.is_dynamic();
         }
 
@@ -456,11 +734,19 @@ impl<'a> Analyzer<'a> {
 
         // If the callee could not be resolved, return a compute kind with certain runtime features.
         let Some(callee) = maybe_callee else {
+            // Keep track of the unresolved callee so strict analyses can report it. The same expression can be visited
+            // once per application so only record it the first time.
+            let unresolved_callee_id = StoreExprId::from((package_id, callee_expr_id));
+            if !self.unresolved_callees.contains(&unresolved_callee_id) {
+                self.unresolved_callees.push(unresolved_callee_id);
+            }
+
             // The value kind of a call expression with an unresolved callee is dynamic but its specific variant depends
             // on the expression's type.
             let value_kind = ValueKind::new_dynamic_from_type(expr_type);
             return ComputeKind::Quantum(QuantumProperties {
                 runtime_features: RuntimeFeatureFlags::CallToUnresolvedCallee,
+                extended_features: ExtendedFeatures::empty(),
                 value_kind,
             });
         };
@@ -483,14 +769,16 @@ impl<'a> Analyzer<'a> {
 
     fn analyze_expr_call_with_udt_callee(&self, args_expr_id: ExprId) -> ComputeKind {
         let application_instance = self.get_current_application_instance();
-        let args_expr_compute_kind = *application_instance.get_expr_compute_kind(args_expr_id);
+        let args_expr_compute_kind = application_instance
+            .get_expr_compute_kind(args_expr_id)
+            .clone();
 
         // To determine the compute kind of an UDT call expression, aggregate the runtime features of the arguments
         // expression.
         let default_value_kind = ValueKind::Element(RuntimeKind::Static);
         let mut compute_kind = ComputeKind::Classical;
         compute_kind =
-            compute_kind.aggregate_runtime_features(args_expr_compute_kind, default_value_kind);
+            compute_kind.aggregate_runtime_features(&args_expr_compute_kind, &default_value_kind);
 
         // If any argument to the UDT constructor is dynamic, then the UDT instance is also dynamic and uses an
         // additional runtime feature.
@@ -502,6 +790,8 @@ impl<'a> Analyzer<'a> {
     }
 
     fn analyze_expr_closure(expr_type: &Ty) -> ComputeKind {
+        // Closures capture locals by value and cannot close over mutable locals (this is enforced by the borrow
+        // checker), so calling a closure never updates the compute kind of a local in the enclosing scope.
         let value_kind = ValueKind::new_dynamic_from_type(expr_type);
         ComputeKind::new_with_runtime_features(RuntimeFeatureFlags::UseOfClosure, value_kind)
     }
@@ -513,23 +803,47 @@ impl<'a> Analyzer<'a> {
         // The compute kind of the expression is determined from the message expression runtime features plus an
         // additional runtime feature if the message expresion is dynamic.
         let application_instance = self.get_current_application_instance();
-        let msg_expr_compute_kind = *application_instance.get_expr_compute_kind(msg_expr_id);
+        let msg_expr_compute_kind = application_instance
+            .get_expr_compute_kind(msg_expr_id)
+            .clone();
         let mut compute_kind = ComputeKind::Classical;
         let default_value_kind = ValueKind::Element(RuntimeKind::Static);
         compute_kind =
-            compute_kind.aggregate_runtime_features(msg_expr_compute_kind, default_value_kind);
+            compute_kind.aggregate_runtime_features(&msg_expr_compute_kind, &default_value_kind);
 
         compute_kind
     }
 
-    fn analyze_expr_field(&mut self, record_expr_id: ExprId, expr_type: &Ty) -> ComputeKind {
+    fn analyze_expr_field(
+        &mut self,
+        record_expr_id: ExprId,
+        field: &Field,
+        expr_type: &Ty,
+    ) -> ComputeKind {
         // Visit the record expression to determine its compute kind.
         self.visit_expr(record_expr_id);
 
+        // When the record is a local variable, only the updates made to the accessed field are relevant.
+        let record_expr = self.get_expr(record_expr_id);
+        if let (ExprKind::Var(Res::Local(local_var_id), _), Field::Path(field_path)) =
+            (&record_expr.kind, field)
+        {
+            if !self.is_tracked_local(*local_var_id) {
+                return ComputeKind::Classical;
+            }
+            let application_instance = self.get_current_application_instance();
+            return application_instance
+                .locals_map
+                .get_local_compute_kind(*local_var_id)
+                .field_compute_kind(&field_path.indices, expr_type);
+        }
+
         // The compute kind of the field expression is determined from the runtime features of the record expression and
         // the value kind adapted to the expression's type.
         let application_instance = self.get_current_application_instance();
-        let record_expr_compute_kind = *application_instance.get_expr_compute_kind(record_expr_id);
+        let record_expr_compute_kind = application_instance
+            .get_expr_compute_kind(record_expr_id)
+            .clone();
         let value_kind = if record_expr_compute_kind.is_dynamic() {
             ValueKind::new_dynamic_from_type(expr_type)
         } else {
@@ -538,7 +852,7 @@ impl<'a> Analyzer<'a> {
 
         let mut compute_kind = ComputeKind::Classical;
         compute_kind =
-            compute_kind.aggregate_runtime_features(record_expr_compute_kind, value_kind);
+            compute_kind.aggregate_runtime_features(&record_expr_compute_kind, &value_kind);
         compute_kind
     }
 
@@ -554,13 +868,12 @@ impl<'a> Analyzer<'a> {
 
         // If the condition expression is dynamic, we push a new dynamic scope.
         let application_instance = self.get_current_application_instance_mut();
-        let condition_expr_compute_kind =
-            *application_instance.get_expr_compute_kind(condition_expr_id);
+        let condition_expr_compute_kind = application_instance
+            .get_expr_compute_kind(condition_expr_id)
+            .clone();
         let within_dynamic_scope = condition_expr_compute_kind.is_dynamic();
         if within_dynamic_scope {
-            application_instance
-                .active_dynamic_scopes
-                .push(condition_expr_id);
+            application_instance.push_dynamic_scope(condition_expr_id);
         }
 
         // Visit the body and otherwise expressions to determine their compute kind.
@@ -581,36 +894,51 @@ impl<'a> Analyzer<'a> {
         let application_instance = self.get_current_application_instance();
         let default_value_kind = ValueKind::new_static_from_type(expr_type);
         let mut compute_kind = ComputeKind::Classical;
-        let condition_expr_compute_kind =
-            *application_instance.get_expr_compute_kind(condition_expr_id);
+        let condition_expr_compute_kind = application_instance
+            .get_expr_compute_kind(condition_expr_id)
+            .clone();
         compute_kind = compute_kind
-            .aggregate_runtime_features(condition_expr_compute_kind, default_value_kind);
-        let body_expr_compute_kind = *application_instance.get_expr_compute_kind(body_expr_id);
+            .aggregate_runtime_features(&condition_expr_compute_kind, &default_value_kind);
+        let body_expr_compute_kind = application_instance
+            .get_expr_compute_kind(body_expr_id)
+            .clone();
         compute_kind =
-            compute_kind.aggregate_runtime_features(body_expr_compute_kind, default_value_kind);
+            compute_kind.aggregate_runtime_features(&body_expr_compute_kind, &default_value_kind);
         if let Some(otherwise_expr_id) = otherwise_expr_id {
-            let otherwise_expr_compute_kind =
-                *application_instance.get_expr_compute_kind(otherwise_expr_id);
+            let otherwise_expr_compute_kind = application_instance
+                .get_expr_compute_kind(otherwise_expr_id)
+                .clone();
             compute_kind = compute_kind
-                .aggregate_runtime_features(otherwise_expr_compute_kind, default_value_kind);
+                .aggregate_runtime_features(&otherwise_expr_compute_kind, &default_value_kind);
         }
 
         // If any of the sub-expressions is dynamic, then the compute kind of an if-expression is dynamic and additional
         // runtime features are aggregated.
-        let is_any_sub_expr_dynamic = condition_expr_compute_kind.is_dynamic()
-            || body_expr_compute_kind.is_dynamic()
-            || otherwise_expr_id.map_or(false, |e| {
-                application_instance.get_expr_compute_kind(e).is_dynamic()
-            });
+        // An arm that fails never produces a value, so when one of the arms fails, the value of the if-expression can
+        // only come from the other arm regardless of the condition.
+        let is_body_failing = self.is_failing_expr(body_expr_id);
+        let is_otherwise_failing = otherwise_expr_id.map_or(false, |e| self.is_failing_expr(e));
+        let is_otherwise_dynamic = otherwise_expr_id.map_or(false, |e| {
+            application_instance.get_expr_compute_kind(e).is_dynamic()
+        });
+        let is_any_sub_expr_dynamic = if is_body_failing || is_otherwise_failing {
+            (!is_body_failing && body_expr_compute_kind.is_dynamic())
+                || (!is_otherwise_failing && is_otherwise_dynamic)
+        } else {
+            condition_expr_compute_kind.is_dynamic()
+                || body_expr_compute_kind.is_dynamic()
+                || is_otherwise_dynamic
+        };
         if is_any_sub_expr_dynamic {
             let dynamic_value_kind = ValueKind::new_dynamic_from_type(expr_type);
             let dynamic_runtime_features =
                 derive_runtime_features_for_value_kind_associated_to_type(
-                    dynamic_value_kind,
+                    &dynamic_value_kind,
                     expr_type,
                 );
             let dynamic_compute_kind = ComputeKind::Quantum(QuantumProperties {
                 runtime_features: dynamic_runtime_features,
+                extended_features: ExtendedFeatures::empty(),
                 value_kind: dynamic_value_kind,
             });
             compute_kind = compute_kind.aggregate(dynamic_compute_kind);
@@ -619,6 +947,33 @@ impl<'a> Analyzer<'a> {
         compute_kind
     }
 
+    /// Whether evaluating the expression always ends in a `fail`, either because it is a fail expression or because it
+    /// is a block with a statement that always fails.
+    fn is_failing_expr(&self, expr_id: ExprId) -> bool {
+        match &self.get_expr(expr_id).kind {
+            ExprKind::Fail(_) => true,
+            ExprKind::Block(block_id) => self.get_block(*block_id).stmts.iter().any(|stmt_id| {
+                match &self.get_stmt(*stmt_id).kind {
+                    StmtKind::Expr(expr_id) | StmtKind::Semi(expr_id) => {
+                        self.is_failing_expr(*expr_id)
+                    }
+                    StmtKind::Item(_) | StmtKind::Local(..) => false,
+                }
+            }),
+            _ => false,
+        }
+    }
+
+    /// Whether the statement is an unconditional return.
+    fn is_return_stmt(&self, stmt_id: StmtId) -> bool {
+        match self.get_stmt(stmt_id).kind {
+            StmtKind::Expr(expr_id) | StmtKind::Semi(expr_id) => {
+                matches!(self.get_expr(expr_id).kind, ExprKind::Return(_))
+            }
+            StmtKind::Item(_) | StmtKind::Local(..) => false,
+        }
+    }
+
     fn analyze_expr_index(
         &mut self,
         array_expr_id: ExprId,
@@ -632,26 +987,34 @@ impl<'a> Analyzer<'a> {
         // The runtime features of the access by index expression are determined by aggregating the runtime features of
         // the array expression and the index expression.
         let application_instance = self.get_current_application_instance();
-        let array_expr_compute_kind = *application_instance.get_expr_compute_kind(array_expr_id);
-        let index_expr_compute_kind = *application_instance.get_expr_compute_kind(index_expr_id);
+        let array_expr_compute_kind = application_instance
+            .get_expr_compute_kind(array_expr_id)
+            .clone();
+        let index_expr_compute_kind = application_instance
+            .get_expr_compute_kind(index_expr_id)
+            .clone();
         let default_value_kind = ValueKind::new_static_from_type(expr_type);
         let mut compute_kind = ComputeKind::Classical;
         compute_kind =
-            compute_kind.aggregate_runtime_features(array_expr_compute_kind, default_value_kind);
+            compute_kind.aggregate_runtime_features(&array_expr_compute_kind, &default_value_kind);
         compute_kind =
-            compute_kind.aggregate_runtime_features(index_expr_compute_kind, default_value_kind);
+            compute_kind.aggregate_runtime_features(&index_expr_compute_kind, &default_value_kind);
 
         // The value kind of the access by index expression depends on whether the content of the array expression is
         // dynamic.
         if let ComputeKind::Quantum(array_quantum_properties) = &array_expr_compute_kind {
-            let ValueKind::Array(content_runtime_value, _) = array_quantum_properties.value_kind
-            else {
-                panic!("the value kind of an array expression must be the array variant");
-            };
-
-            if matches!(content_runtime_value, RuntimeKind::Dynamic) {
-                let dynamic_value_kind = ValueKind::new_dynamic_from_type(expr_type);
-                compute_kind.aggregate_value_kind(dynamic_value_kind);
+            match &array_quantum_properties.value_kind {
+                ValueKind::Array(content_runtime_value, _) => {
+                    if matches!(content_runtime_value, RuntimeKind::Dynamic) {
+                        let dynamic_value_kind = ValueKind::new_dynamic_from_type(expr_type);
+                        compute_kind.aggregate_value_kind(dynamic_value_kind);
+                    }
+                }
+                // The elements of a nested array are arrays whose value kind is known.
+                ValueKind::NestedArray(content_value_kind, _) => {
+                    compute_kind.aggregate_value_kind(content_value_kind.as_ref().clone());
+                }
+                _ => panic!("the value kind of an array expression must be an array variant"),
             }
         }
 
@@ -673,13 +1036,13 @@ impl<'a> Analyzer<'a> {
         // The compute kind of a range expression is the aggregation of its start, step and end expressions.
         let application_instance = self.get_current_application_instance();
         let start_expr_compute_kind = start_expr_id.map_or(ComputeKind::Classical, |e| {
-            *application_instance.get_expr_compute_kind(e)
+            application_instance.get_expr_compute_kind(e).clone()
         });
         let step_expr_compute_kind = step_expr_id.map_or(ComputeKind::Classical, |e| {
-            *application_instance.get_expr_compute_kind(e)
+            application_instance.get_expr_compute_kind(e).clone()
         });
         let end_expr_compute_kind = end_expr_id.map_or(ComputeKind::Classical, |e| {
-            *application_instance.get_expr_compute_kind(e)
+            application_instance.get_expr_compute_kind(e).clone()
         });
         let mut compute_kind = ComputeKind::Classical;
         compute_kind = compute_kind.aggregate(start_expr_compute_kind);
@@ -708,15 +1071,39 @@ impl<'a> Analyzer<'a> {
         } else {
             ComputeKind::Quantum(QuantumProperties {
                 runtime_features: RuntimeFeatureFlags::ReturnWithinDynamicScope,
+                extended_features: ExtendedFeatures::empty(),
                 value_kind: ValueKind::Element(RuntimeKind::Static),
             })
         };
 
         // Now just aggregate the runtime features of the value expression.
         let default_value_kind = ValueKind::Element(RuntimeKind::Static);
-        let value_expr_compute_kind = *application_instance.get_expr_compute_kind(value_expr_id);
+        let value_expr_compute_kind = application_instance
+            .get_expr_compute_kind(value_expr_id)
+            .clone();
         compute_kind =
-            compute_kind.aggregate_runtime_features(value_expr_compute_kind, default_value_kind);
+            compute_kind.aggregate_runtime_features(&value_expr_compute_kind, &default_value_kind);
+
+        // A callable returned within a dynamic scope is dynamically determined because which return expression is reached
+        // depends on a dynamic value, so it uses the runtime features of a dynamic arrow.
+        let value_expr_type = &self.get_expr(value_expr_id).ty;
+        if matches!(value_expr_type, Ty::Arrow(_))
+            && !application_instance.active_dynamic_scopes.is_empty()
+        {
+            let dynamic_value_kind = ValueKind::new_dynamic_from_type(value_expr_type);
+            let dynamic_runtime_features =
+                derive_runtime_features_for_value_kind_associated_to_type(
+                    &dynamic_value_kind,
+                    value_expr_type,
+                );
+            compute_kind = compute_kind.aggregate_runtime_features(
+                &ComputeKind::new_with_runtime_features(
+                    dynamic_runtime_features,
+                    default_value_kind.clone(),
+                ),
+                &default_value_kind,
+            );
+        }
         compute_kind
     }
 
@@ -732,9 +1119,9 @@ impl<'a> Analyzer<'a> {
                     self.visit_expr(*expr_id);
                     let application_instance = self.get_current_application_instance();
                     let component_compute_kind =
-                        *application_instance.get_expr_compute_kind(*expr_id);
+                        application_instance.get_expr_compute_kind(*expr_id).clone();
                     compute_kind = compute_kind
-                        .aggregate_runtime_features(component_compute_kind, default_value_kind);
+                        .aggregate_runtime_features(&component_compute_kind, &default_value_kind);
                     has_dynamic_components |= component_compute_kind.is_dynamic();
                 }
                 StringComponent::Lit(_) => {
@@ -761,18 +1148,28 @@ impl<'a> Analyzer<'a> {
         let default_value_kind = ValueKind::Element(RuntimeKind::Static);
         let mut compute_kind = ComputeKind::Classical;
         let mut has_dynamic_sub_exprs = false;
+        let mut element_value_kinds = Vec::with_capacity(exprs.len());
         for expr_id in exprs {
             self.visit_expr(*expr_id);
+            let expr_ty = &self.get_expr(*expr_id).ty;
             let application_instance = self.get_current_application_instance();
-            let expr_compute_kind = *application_instance.get_expr_compute_kind(*expr_id);
+            let expr_compute_kind = application_instance.get_expr_compute_kind(*expr_id);
             compute_kind =
-                compute_kind.aggregate_runtime_features(expr_compute_kind, default_value_kind);
+                compute_kind.aggregate_runtime_features(expr_compute_kind, &default_value_kind);
             has_dynamic_sub_exprs |= expr_compute_kind.is_dynamic();
+            element_value_kinds.push(
+                expr_compute_kind.value_kind_or_default(ValueKind::new_static_from_type(expr_ty)),
+            );
         }
 
-        // If any of the sub-expressions is dynamic, then the tuple expression is dynamic as well.
+        // If any of the sub-expressions is dynamic, then the tuple expression is dynamic as well, and its value kind
+        // keeps track of which of its elements are dynamic.
         if has_dynamic_sub_exprs {
-            compute_kind.aggregate_value_kind(ValueKind::Element(RuntimeKind::Dynamic));
+            let ComputeKind::Quantum(quantum_properties) = &mut compute_kind else {
+                panic!("the compute kind of a tuple expression cannot have dynamic elements and be classical");
+            };
+
+            quantum_properties.value_kind = ValueKind::Tuple(element_value_kinds);
         }
 
         compute_kind
@@ -784,7 +1181,9 @@ impl<'a> Analyzer<'a> {
 
         // The compute kind of an unary expression is the same as the compute kind of its operand expression.
         let application_instance = self.get_current_application_instance();
-        *application_instance.get_expr_compute_kind(operand_expr_id)
+        application_instance
+            .get_expr_compute_kind(operand_expr_id)
+            .clone()
     }
 
     fn analyze_expr_update_field(
@@ -799,15 +1198,18 @@ impl<'a> Analyzer<'a> {
         // The runtime features of an update field expression are determined by aggregating the runtime features of the
         // record and replace expressions.
         let application_instance = self.get_current_application_instance();
-        let record_expr_compute_kind = *application_instance.get_expr_compute_kind(record_expr_id);
-        let replace_expr_compute_kind =
-            *application_instance.get_expr_compute_kind(replace_expr_id);
+        let record_expr_compute_kind = application_instance
+            .get_expr_compute_kind(record_expr_id)
+            .clone();
+        let replace_expr_compute_kind = application_instance
+            .get_expr_compute_kind(replace_expr_id)
+            .clone();
         let default_value_kind = ValueKind::Element(RuntimeKind::Static);
         let mut compute_kind = ComputeKind::Classical;
         compute_kind =
-            compute_kind.aggregate_runtime_features(record_expr_compute_kind, default_value_kind);
-        compute_kind =
-            compute_kind.aggregate_runtime_features(replace_expr_compute_kind, default_value_kind);
+            compute_kind.aggregate_runtime_features(&record_expr_compute_kind, &default_value_kind);
+        compute_kind = compute_kind
+            .aggregate_runtime_features(&replace_expr_compute_kind, &default_value_kind);
 
         // If either the record or the replace expressions are dynamic, the update field expression is dynamic as well.
         if record_expr_compute_kind.is_dynamic() || replace_expr_compute_kind.is_dynamic() {
@@ -831,27 +1233,33 @@ impl<'a> Analyzer<'a> {
         // The runtime features of an update index expression is determined by aggregating the runtime features of its
         // sub-expressions, with some nuanced considerations.
         let application_instance = self.get_current_application_instance();
-        let array_expr_compute_kind = *application_instance.get_expr_compute_kind(array_expr_id);
-        let index_expr_compute_kind = *application_instance.get_expr_compute_kind(index_expr_id);
-        let replacement_value_expr_compute_kind =
-            *application_instance.get_expr_compute_kind(replacement_value_expr_id);
+        let array_expr_compute_kind = application_instance
+            .get_expr_compute_kind(array_expr_id)
+            .clone();
+        let index_expr_compute_kind = application_instance
+            .get_expr_compute_kind(index_expr_id)
+            .clone();
+        let replacement_value_expr_compute_kind = application_instance
+            .get_expr_compute_kind(replacement_value_expr_id)
+            .clone();
         let default_value_kind = ValueKind::Array(RuntimeKind::Static, RuntimeKind::Static);
         let mut compute_kind = ComputeKind::Classical;
         compute_kind =
-            compute_kind.aggregate_runtime_features(array_expr_compute_kind, default_value_kind);
+            compute_kind.aggregate_runtime_features(&array_expr_compute_kind, &default_value_kind);
         compute_kind =
-            compute_kind.aggregate_runtime_features(index_expr_compute_kind, default_value_kind);
+            compute_kind.aggregate_runtime_features(&index_expr_compute_kind, &default_value_kind);
         compute_kind = compute_kind
-            .aggregate_runtime_features(replacement_value_expr_compute_kind, default_value_kind);
+            .aggregate_runtime_features(&replacement_value_expr_compute_kind, &default_value_kind);
 
         // If the index expression is dynamic, an additional runtime feature is used.
         if index_expr_compute_kind.is_dynamic() {
             let additional_compute_kind = ComputeKind::Quantum(QuantumProperties {
                 runtime_features: RuntimeFeatureFlags::UseOfDynamicIndex,
-                value_kind: default_value_kind,
+                extended_features: ExtendedFeatures::empty(),
+                value_kind: default_value_kind.clone(),
             });
             compute_kind = compute_kind
-                .aggregate_runtime_features(additional_compute_kind, default_value_kind);
+                .aggregate_runtime_features(&additional_compute_kind, &default_value_kind);
         }
 
         // The value kind of the update index expression is based on the value kind of the array expression.
@@ -877,10 +1285,32 @@ impl<'a> Analyzer<'a> {
             // Gather the current compute kind of the local.
             Res::Local(local_var_id) => {
                 let application_instance = self.get_current_application_instance();
-                let local_compute_kind = application_instance
+                let Some(local_compute_kind) = application_instance
                     .locals_map
-                    .get_local_compute_kind(*local_var_id);
-                local_compute_kind.compute_kind
+                    .find_local_compute_kind(*local_var_id)
+                else {
+                    // When analyzing an expression in isolation, the locals bound outside of it are unknown so they
+                    // are considered classical.
+                    assert!(
+                        self.is_standalone_context(),
+                        "local compute kind does not exist"
+                    );
+                    return ComputeKind::Classical;
+                };
+                let mut compute_kind = local_compute_kind.whole_compute_kind();
+
+                // Updates to individual fields can make the whole local dynamic, in which case additional runtime
+                // features might be needed depending on the type of the local.
+                if !local_compute_kind.fields.is_empty() {
+                    if let ComputeKind::Quantum(quantum_properties) = &mut compute_kind {
+                        quantum_properties.runtime_features |=
+                            derive_runtime_features_for_value_kind_associated_to_type(
+                                &quantum_properties.value_kind,
+                                &local_compute_kind.local.ty,
+                            );
+                    }
+                }
+                compute_kind
             }
             Res::Err => panic!("unexpected error resolution"),
         }
@@ -892,13 +1322,12 @@ impl<'a> Analyzer<'a> {
 
         // If the condition expression is dynamic, we push a new dynamic scope before visiting the block.
         let application_instance = self.get_current_application_instance_mut();
-        let condition_expr_compute_kind =
-            *application_instance.get_expr_compute_kind(condition_expr_id);
+        let mut condition_expr_compute_kind = application_instance
+            .get_expr_compute_kind(condition_expr_id)
+            .clone();
         let within_dynamic_scope = condition_expr_compute_kind.is_dynamic();
         if within_dynamic_scope {
-            application_instance
-                .active_dynamic_scopes
-                .push(condition_expr_id);
+            application_instance.push_dynamic_scope(condition_expr_id);
         }
         self.visit_block(block_id);
         if within_dynamic_scope {
@@ -908,17 +1337,38 @@ impl<'a> Analyzer<'a> {
                 .pop()
                 .expect("at least one dynamic scope should exist");
             assert!(dynamic_scope_expr_id == condition_expr_id);
+        } else {
+            // The condition can depend on variables updated within the loop body (e.g. repeat-until loops are lowered
+            // into a while loop whose condition is a mutable variable updated at the end of each iteration), so visit
+            // the condition again and, if it became dynamic, visit the block again within a dynamic scope.
+            self.visit_expr(condition_expr_id);
+            let application_instance = self.get_current_application_instance_mut();
+            condition_expr_compute_kind = application_instance
+                .get_expr_compute_kind(condition_expr_id)
+                .clone();
+            if condition_expr_compute_kind.is_dynamic() {
+                application_instance.push_dynamic_scope(condition_expr_id);
+                self.visit_block(block_id);
+                let application_instance = self.get_current_application_instance_mut();
+                let dynamic_scope_expr_id = application_instance
+                    .active_dynamic_scopes
+                    .pop()
+                    .expect("at least one dynamic scope should exist");
+                assert!(dynamic_scope_expr_id == condition_expr_id);
+            }
         }
 
         // Return the aggregated runtime features of the condition expression and the block.
         let application_instance = self.get_current_application_instance();
-        let block_compute_kind = *application_instance.get_block_compute_kind(block_id);
+        let block_compute_kind = application_instance
+            .get_block_compute_kind(block_id)
+            .clone();
         let default_value_kind = ValueKind::Element(RuntimeKind::Static);
         let mut compute_kind = ComputeKind::Classical;
         compute_kind = compute_kind
-            .aggregate_runtime_features(condition_expr_compute_kind, default_value_kind);
+            .aggregate_runtime_features(&condition_expr_compute_kind, &default_value_kind);
         compute_kind =
-            compute_kind.aggregate_runtime_features(block_compute_kind, default_value_kind);
+            compute_kind.aggregate_runtime_features(&block_compute_kind, &default_value_kind);
 
         // If the condition is dynamic, we require an additional runtime feature.
         if condition_expr_compute_kind.is_dynamic() {
@@ -928,11 +1378,27 @@ impl<'a> Analyzer<'a> {
             quantum_properties.runtime_features |= RuntimeFeatureFlags::LoopWithDynamicCondition;
         }
 
+        // If the loop possibly never terminates, we require an additional runtime feature. Like for cyclic functions,
+        // loops within functions are inherently classical, so the runtime feature only annotates loops that are already
+        // quantum (e.g. because they depend on dynamic arguments).
+        let package = self.package_store.get(self.get_current_package_id());
+        if is_possibly_non_terminating_loop(condition_expr_id, block_id, package)
+            && (!self.is_within_function() || matches!(compute_kind, ComputeKind::Quantum(_)))
+        {
+            compute_kind = compute_kind.aggregate_runtime_features(
+                &ComputeKind::new_with_runtime_features(
+                    RuntimeFeatureFlags::PossiblyNonTerminating,
+                    default_value_kind.clone(),
+                ),
+                &default_value_kind,
+            );
+        }
+
         compute_kind
     }
 
     // Analyzes the currently active callable assuming it is intrinsic.
-    fn analyze_intrinsic_callable(&mut self) {
+    fn analyze_intrinsic_callable(&mut self, decl: &CallableDecl) {
         // Check whether the callable has already been analyzed.
         let current_item_context = self.get_current_item_context();
         let body_specialization_id =
@@ -945,17 +1411,42 @@ impl<'a> Analyzer<'a> {
             return;
         }
 
-        // Determine the application generator set depending on whether the callable is a function or an operation.
+        // Determine the application generator set depending on whether the callable is debug-only, a function or an
+        // operation.
         let callable_context = current_item_context.get_callable_context();
-        let application_generator_set = match callable_context.kind {
-            CallableKind::Function => {
-                derive_intrinsic_function_application_generator_set(callable_context)
-            }
-            CallableKind::Operation => {
-                derive_instrinsic_operation_application_generator_set(callable_context)
+        let is_debug_only = self
+            .debug_only_intrinsics_hook
+            .is_some_and(|hook| hook(decl));
+        let mut application_generator_set = if is_debug_only {
+            derive_debug_only_intrinsic_application_generator_set(callable_context)
+        } else {
+            match callable_context.kind {
+                CallableKind::Function => {
+                    derive_intrinsic_function_application_generator_set(callable_context)
+                }
+                CallableKind::Operation => {
+                    derive_instrinsic_operation_application_generator_set(callable_context)
+                }
             }
         };
 
+        // If a hook was provided, aggregate the vendor-specific runtime features it derives for the callable to the
+        // inherent compute kind so they are part of any application of the callable.
+        let extended_features = self
+            .intrinsic_features_hook
+            .map(|hook| hook(decl))
+            .unwrap_or_default();
+        if !extended_features.is_empty() {
+            let extended_compute_kind = ComputeKind::Quantum(QuantumProperties {
+                runtime_features: RuntimeFeatureFlags::empty(),
+                extended_features,
+                value_kind: ValueKind::new_static_from_type(&callable_context.output_type),
+            });
+            application_generator_set.inherent = application_generator_set
+                .inherent
+                .aggregate(extended_compute_kind);
+        }
+
         // Insert the generator set in the entry corresponding to the body specialization of the callable.
         self.package_store_compute_properties
             .insert_spec(body_specialization_id, application_generator_set);
@@ -968,10 +1459,16 @@ impl<'a> Analyzer<'a> {
         assert!(popped_item_id == item_id);
     }
 
-    fn analyze_package_internal(&mut self, package_id: PackageId, package: &'a Package) {
+    fn analyze_package_internal(
+        &mut self,
+        package_id: PackageId,
+        package: &'a Package,
+        on_item_analyzed: &mut impl FnMut(),
+    ) {
         // Analyze all top level items.
         for (local_item_id, item) in &package.items {
             self.analyze_item((package_id, local_item_id).into(), item);
+            on_item_analyzed();
         }
 
         // Analyze top-level statements, which should be the only ones unanalyzed at this point.
@@ -1016,25 +1513,11 @@ impl<'a> Analyzer<'a> {
 
         // Continue with the analysis differently depending on whether the callable is an intrinsic or not.
         match &callable_decl.implementation {
-            CallableImpl::Intrinsic => self.analyze_intrinsic_callable(),
+            CallableImpl::Intrinsic => self.analyze_intrinsic_callable(callable_decl),
             CallableImpl::Spec(spec_impl) => {
                 // Only analyze the specialization that corresponds to the provided ID. Otherwise, we can get into an
                 // infinite analysis loop.
-                let spec_decl = match id.functor_set_value {
-                    FunctorSetValue::Empty => &spec_impl.body,
-                    FunctorSetValue::Adj => spec_impl
-                        .adj
-                        .as_ref()
-                        .expect("adj specialization should exist"),
-                    FunctorSetValue::Ctl => spec_impl
-                        .ctl
-                        .as_ref()
-                        .expect("ctl specialization should exist"),
-                    FunctorSetValue::CtlAdj => spec_impl
-                        .ctl_adj
-                        .as_ref()
-                        .expect("ctladj specialization should exist"),
-                };
+                let spec_decl = get_spec_decl(spec_impl, id.functor_set_value);
                 self.analyze_spec_decl(spec_decl, id.functor_set_value);
             }
         };
@@ -1064,6 +1547,13 @@ impl<'a> Analyzer<'a> {
         let spec_context = self.clear_current_spec_context();
         assert!(spec_context.functor_set_value == functor_set_value);
 
+        // Keep track of the deepest nesting of dynamic scopes across all the specializations of the callable.
+        self.package_store_compute_properties
+            .update_dynamic_scope_depth(
+                global_spec_id.callable,
+                spec_context.builder.max_dynamic_scope_depth(),
+            );
+
         // Save the analysis to the corresponding package compute properties.
         let package_compute_properties = self.package_store_compute_properties.get_mut(package_id);
         let application_generator_set = spec_context
@@ -1088,10 +1578,7 @@ impl<'a> Analyzer<'a> {
             ty: pat.ty.clone(),
             kind: local_kind,
         };
-        let local_compute_kind = LocalComputeKind {
-            local,
-            compute_kind,
-        };
+        let local_compute_kind = LocalComputeKind::new(local, compute_kind);
         application_instance
             .locals_map
             .insert(ident.id, local_compute_kind);
@@ -1108,7 +1595,7 @@ impl<'a> Analyzer<'a> {
         match &pat.kind {
             PatKind::Bind(ident) => {
                 let application_instance = self.get_current_application_instance();
-                let compute_kind = *application_instance.get_expr_compute_kind(expr_id);
+                let compute_kind = application_instance.get_expr_compute_kind(expr_id).clone();
                 let local_kind = match mutability {
                     Mutability::Immutable => LocalKind::Immutable(expr_id),
                     Mutability::Mutable => LocalKind::Mutable,
@@ -1136,12 +1623,22 @@ impl<'a> Analyzer<'a> {
         mutability: Mutability,
         pat_id: PatId,
         expr_id: ExprId,
+    ) {
+        let application_instance = self.get_current_application_instance();
+        let compute_kind = application_instance.get_expr_compute_kind(expr_id).clone();
+        self.bind_compute_kind_to_pattern(mutability, pat_id, expr_id, compute_kind);
+    }
+
+    fn bind_compute_kind_to_pattern(
+        &mut self,
+        mutability: Mutability,
+        pat_id: PatId,
+        expr_id: ExprId,
+        compute_kind: ComputeKind,
     ) {
         let pat = self.get_pat(pat_id);
         match &pat.kind {
             PatKind::Bind(ident) => {
-                let application_instance = self.get_current_application_instance();
-                let compute_kind = *application_instance.get_expr_compute_kind(expr_id);
                 let local_kind = match mutability {
                     Mutability::Immutable => LocalKind::Immutable(expr_id),
                     Mutability::Mutable => LocalKind::Mutable,
@@ -1149,8 +1646,29 @@ impl<'a> Analyzer<'a> {
                 self.bind_compute_kind_to_ident(pat, ident, local_kind, compute_kind);
             }
             PatKind::Tuple(pats) => {
-                for pat_id in pats {
-                    self.bind_fixed_expr_compute_kind_to_pattern(mutability, *pat_id, expr_id);
+                for (index, pat_id) in pats.iter().enumerate() {
+                    // When the value kind of the tuple is known element-wise, each sub-pattern only gets the value kind
+                    // of its corresponding element. The runtime features are conservatively kept for all of them.
+                    let element_compute_kind = match &compute_kind {
+                        ComputeKind::Quantum(quantum_properties) => {
+                            match &quantum_properties.value_kind {
+                                ValueKind::Tuple(value_kinds) => {
+                                    ComputeKind::Quantum(QuantumProperties {
+                                        value_kind: value_kinds[index].clone(),
+                                        ..quantum_properties.clone()
+                                    })
+                                }
+                                _ => compute_kind.clone(),
+                            }
+                        }
+                        ComputeKind::Classical => ComputeKind::Classical,
+                    };
+                    self.bind_compute_kind_to_pattern(
+                        mutability,
+                        *pat_id,
+                        expr_id,
+                        element_compute_kind,
+                    );
                 }
             }
             PatKind::Discard => {
@@ -1228,6 +1746,78 @@ impl<'a> Analyzer<'a> {
         }
     }
 
+    fn is_global_phase_only_application(&self, callee: &Callee, arg_exprs: &[ExprId]) -> bool {
+        // The controlled variants of global phase operations do have an observable effect on the control qubits.
+        if callee.functor_app.controlled > 0 {
+            return false;
+        }
+
+        if Some(callee.item) == self.apply_global_phase_id {
+            return true;
+        }
+
+        // A rotation about the identity Pauli operator only applies a global phase.
+        Some(callee.item) == self.r_id
+            && arg_exprs.first().is_some_and(|arg_expr_id| {
+                matches!(
+                    self.get_expr(*arg_expr_id).kind,
+                    ExprKind::Lit(Lit::Pauli(Pauli::I))
+                )
+            })
+    }
+
+    fn is_qubit_release_call(&self, callee_expr_id: ExprId) -> bool {
+        let package_id = self.get_current_package_id();
+        let package = self.package_store.get(package_id);
+        let application_instance = self.get_current_application_instance();
+        let Some(callee) = try_resolve_callee(
+            callee_expr_id,
+            package_id,
+            package,
+            &application_instance.locals_map,
+        ) else {
+            return false;
+        };
+        matches!(
+            self.package_store.get_global(callee.item),
+            Some(Global::Callable(callable_decl))
+                if callable_decl.name.name.as_ref() == "__quantum__rt__qubit_release"
+        )
+    }
+
+    /// Whether the compute kind of the local is tracked in the current application instance. Only a standalone context
+    /// can use locals bound outside of it, which are considered classical and whose updates are not tracked.
+    fn is_tracked_local(&self, local_var_id: LocalVarId) -> bool {
+        let is_tracked = self
+            .get_current_application_instance()
+            .locals_map
+            .find_local_compute_kind(local_var_id)
+            .is_some();
+        assert!(
+            is_tracked || self.is_standalone_context(),
+            "local compute kind does not exist"
+        );
+        is_tracked
+    }
+
+    fn is_within_function(&self) -> bool {
+        matches!(
+            self.get_current_context(),
+            AnalysisContext::Item(item_context)
+                if matches!(item_context.get_callable_context().kind, CallableKind::Function)
+        )
+    }
+
+    fn is_standalone_context(&self) -> bool {
+        matches!(
+            self.get_current_context(),
+            AnalysisContext::TopLevel(TopLevelContext {
+                is_standalone: true,
+                ..
+            })
+        )
+    }
+
     fn pop_item_context(&mut self) -> StoreItemId {
         let popped_context = self
             .active_contexts
@@ -1280,6 +1870,47 @@ impl<'a> Analyzer<'a> {
             .set_current_spec_context(spec_context);
     }
 
+    fn try_resolve_closure_callee(
+        &self,
+        expr_id: ExprId,
+    ) -> Option<(&'a [LocalVarId], LocalItemId)> {
+        // This is a best effort attempt to resolve a closure callee that only follows immutable locals.
+        let expr = self.get_expr(expr_id);
+        match &expr.kind {
+            ExprKind::Closure(captures, closure_item) => Some((captures, *closure_item)),
+            ExprKind::Var(Res::Local(local_var_id), _) => {
+                let local_compute_kind = self
+                    .get_current_application_instance()
+                    .locals_map
+                    .find_local_compute_kind(*local_var_id)?;
+                match local_compute_kind.local.kind {
+                    LocalKind::Immutable(expr_id) => self.try_resolve_closure_callee(expr_id),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Gets the top-level statements of the package in the order they were added, which are the ones that do not belong
+    /// to any block.
+    fn top_level_stmts(&self, package_id: PackageId) -> Vec<StmtId> {
+        let package = self.package_store.get(package_id);
+        let block_stmts = package
+            .blocks
+            .values()
+            .flat_map(|block| block.stmts.iter().copied())
+            .collect::<FxHashSet<_>>();
+        let mut top_level_stmts = package
+            .stmts
+            .iter()
+            .map(|(stmt_id, _)| stmt_id)
+            .filter(|stmt_id| !block_stmts.contains(stmt_id))
+            .collect::<Vec<_>>();
+        top_level_stmts.sort_unstable();
+        top_level_stmts
+    }
+
     fn unanalyzed_stmts(&self, package_id: PackageId) -> Vec<StmtId> {
         let package = self.package_store.get(package_id);
         let mut unanalyzed_stmts = Vec::new();
@@ -1295,6 +1926,54 @@ impl<'a> Analyzer<'a> {
         unanalyzed_stmts
     }
 
+    fn derive_updated_compute_kind(&self, value_expr_id: ExprId, target_ty: &Ty) -> ComputeKind {
+        // The updated compute kind is based on the compute kind of the value expression.
+        let application_instance = self.get_current_application_instance();
+        let value_expr_compute_kind = application_instance
+            .get_expr_compute_kind(value_expr_id)
+            .clone();
+
+        // Since the updated compute kind is bound to the target, the value kind must match the target's type. In some
+        // cases, there might be some loss of granularity on the value kind (e.g. assigning an array to a UDT variable).
+        let mut value_kind = ValueKind::new_static_from_type(target_ty);
+        if let ComputeKind::Quantum(value_expr_quantum_properties) = &value_expr_compute_kind {
+            value_expr_quantum_properties
+                .value_kind
+                .project_onto_variant(&mut value_kind);
+        }
+
+        let mut updated_compute_kind = ComputeKind::Classical;
+        updated_compute_kind =
+            updated_compute_kind.aggregate_runtime_features(&value_expr_compute_kind, &value_kind);
+
+        // If the target is updated within a dynamic scope, the updated value should be dynamic and additional runtime
+        // features may apply.
+        if !application_instance.active_dynamic_scopes.is_empty() {
+            let dynamic_value_kind = ValueKind::new_dynamic_from_type(target_ty);
+            let dynamic_runtime_features =
+                derive_runtime_features_for_value_kind_associated_to_type(
+                    &dynamic_value_kind,
+                    target_ty,
+                );
+            let dynamic_compute_kind = ComputeKind::new_with_runtime_features(
+                dynamic_runtime_features,
+                dynamic_value_kind,
+            );
+            updated_compute_kind = updated_compute_kind.aggregate(dynamic_compute_kind);
+        }
+
+        // If the updated compute kind is dynamic, include additional properties depending on the type of the target.
+        if let Some(value_kind) = updated_compute_kind.value_kind() {
+            let ComputeKind::Quantum(updated_quantum_properties) = &mut updated_compute_kind else {
+                panic!("expected Quantum variant of Compute Kind");
+            };
+            updated_quantum_properties.runtime_features |=
+                derive_runtime_features_for_value_kind_associated_to_type(&value_kind, target_ty);
+        }
+
+        updated_compute_kind
+    }
+
     fn update_locals_compute_kind(
         &mut self,
         assignee_expr_id: ExprId,
@@ -1307,67 +1986,25 @@ impl<'a> Analyzer<'a> {
                 let Res::Local(local_var_id) = res else {
                     panic!("expected a local variable");
                 };
-
-                // The updated compute kind is based on the compute kind of the value expression.
-                let application_instance = self.get_current_application_instance();
-                let value_expr_compute_kind =
-                    *application_instance.get_expr_compute_kind(value_expr_id);
+                if !self.is_tracked_local(*local_var_id) {
+                    return self.derive_updated_compute_kind(value_expr_id, &assignee_expr.ty);
+                }
 
                 // Since the local variable compute kind is what will be updated, the value kind must match the local
-                // variable's type. In some cases, there might be some loss of granularity on the value kind (e.g.
-                // assigning an array to a UDT variable field since we do not track individual UDT fields).
-                let local_var_compute_kind = application_instance
+                // variable's type.
+                let application_instance = self.get_current_application_instance();
+                let local_ty = application_instance
                     .locals_map
-                    .get_local_compute_kind(*local_var_id);
-                let mut value_kind =
-                    ValueKind::new_static_from_type(&local_var_compute_kind.local.ty);
-                if let ComputeKind::Quantum(value_expr_quantum_properties) = value_expr_compute_kind
-                {
-                    value_expr_quantum_properties
-                        .value_kind
-                        .project_onto_variant(&mut value_kind);
-                }
-
-                let mut updated_compute_kind = ComputeKind::Classical;
-                updated_compute_kind = updated_compute_kind
-                    .aggregate_runtime_features(value_expr_compute_kind, value_kind);
-
-                // If a local is updated within a dynamic scope, the updated value of the local variable should be
-                // dynamic and additional runtime features may apply.
-                if !application_instance.active_dynamic_scopes.is_empty() {
-                    let local_type = &local_var_compute_kind.local.ty;
-                    let dynamic_value_kind = ValueKind::new_dynamic_from_type(local_type);
-                    let dynamic_runtime_features =
-                        derive_runtime_features_for_value_kind_associated_to_type(
-                            dynamic_value_kind,
-                            local_type,
-                        );
-                    let dynamic_compute_kind = ComputeKind::new_with_runtime_features(
-                        dynamic_runtime_features,
-                        dynamic_value_kind,
-                    );
-                    updated_compute_kind = updated_compute_kind.aggregate(dynamic_compute_kind);
-                }
-
-                // If the updated compute kind is dynamic, include additional properties depending on the type of the
-                // local variable.
-                if let Some(value_kind) = updated_compute_kind.value_kind() {
-                    let ComputeKind::Quantum(updated_quantum_properties) =
-                        &mut updated_compute_kind
-                    else {
-                        panic!("expected Quantum variant of Compute Kind");
-                    };
-                    updated_quantum_properties.runtime_features |=
-                        derive_runtime_features_for_value_kind_associated_to_type(
-                            value_kind,
-                            &local_var_compute_kind.local.ty,
-                        );
-                }
-
+                    .get_local_compute_kind(*local_var_id)
+                    .local
+                    .ty
+                    .clone();
+                let updated_compute_kind =
+                    self.derive_updated_compute_kind(value_expr_id, &local_ty);
                 let application_instance = self.get_current_application_instance_mut();
                 application_instance
                     .locals_map
-                    .aggregate_compute_kind(*local_var_id, updated_compute_kind);
+                    .aggregate_compute_kind(*local_var_id, updated_compute_kind.clone());
                 updated_compute_kind
             }
             ExprKind::Tuple(assignee_exprs) => {
@@ -1387,8 +2024,8 @@ impl<'a> Analyzer<'a> {
                         *element_value_expr_id,
                     );
                     updated_compute_kind = updated_compute_kind.aggregate_runtime_features(
-                        element_update_compute_kind,
-                        default_value_kind,
+                        &element_update_compute_kind,
+                        &default_value_kind,
                     );
                 }
                 updated_compute_kind
@@ -1426,19 +2063,30 @@ impl<'a> Visitor<'a> for Analyzer<'a> {
         // Visit each statement in the block and aggregate its compute kind.
         let default_value_kind = ValueKind::new_static_from_type(&block.ty);
         let mut block_compute_kind = ComputeKind::Classical;
+        let mut is_after_return = false;
+        let mut is_last_stmt_unreachable = false;
         for stmt_id in &block.stmts {
             // Visiting a statement performs its analysis for the current application instance.
             self.visit_stmt(*stmt_id);
 
+            // Statements that follow an unconditional return are never executed, so they are still analyzed but do not
+            // contribute to the block's compute kind.
+            is_last_stmt_unreachable = is_after_return;
+            if is_after_return {
+                continue;
+            }
+
             // Now, we can query the statement's compute kind and aggregate it to the block's compute kind.
             let application_instance = self.get_current_application_instance();
-            let stmt_compute_kind = *application_instance.get_stmt_compute_kind(*stmt_id);
+            let stmt_compute_kind = application_instance.get_stmt_compute_kind(*stmt_id).clone();
             block_compute_kind = block_compute_kind
-                .aggregate_runtime_features(stmt_compute_kind, default_value_kind);
+                .aggregate_runtime_features(&stmt_compute_kind, &default_value_kind);
+            is_after_return = self.is_return_stmt(*stmt_id);
         }
 
         // Update the block's value kind if its non-unit, based on the value kind of its last statement's expression.
-        if block.ty != Ty::UNIT {
+        // When the last statement is unreachable, the block never produces a value of its own.
+        if block.ty != Ty::UNIT && !is_last_stmt_unreachable {
             let last_stmt_id = block
                 .stmts
                 .last()
@@ -1472,15 +2120,11 @@ impl<'a> Visitor<'a> for Analyzer<'a> {
             derive_callable_input_params(decl, &self.package_store.get(package_id).pats);
         let current_callable_context = self.get_current_item_context_mut();
         current_callable_context.set_callable_context(decl.kind, input_params, decl.output.clone());
-        self.visit_callable_impl(&decl.implementation);
-    }
 
-    fn visit_callable_impl(&mut self, callable_impl: &'a CallableImpl) {
-        match callable_impl {
-            CallableImpl::Intrinsic => self.analyze_intrinsic_callable(),
-            CallableImpl::Spec(spec_impl) => {
-                self.visit_spec_impl(spec_impl);
-            }
+        // Intrinsic callables are analyzed using their declaration since it is what the intrinsic features hook uses.
+        match &decl.implementation {
+            CallableImpl::Intrinsic => self.analyze_intrinsic_callable(decl),
+            CallableImpl::Spec(spec_impl) => self.visit_spec_impl(spec_impl),
         };
     }
 
@@ -1491,19 +2135,23 @@ impl<'a> Visitor<'a> for Analyzer<'a> {
             ExprKind::ArrayRepeat(value_expr_id, size_expr_id) => {
                 self.analyze_expr_array_repeat(*value_expr_id, *size_expr_id)
             }
-            ExprKind::Assign(assignee_expr_id, value_expr_id)
-            | ExprKind::AssignField(assignee_expr_id, _, value_expr_id)
-            | ExprKind::AssignOp(_, assignee_expr_id, value_expr_id) => {
+            ExprKind::Assign(assignee_expr_id, value_expr_id) => {
                 self.analyze_expr_assign(*assignee_expr_id, *value_expr_id)
             }
+            ExprKind::AssignOp(op, assignee_expr_id, value_expr_id) => {
+                self.analyze_expr_assign_op(*op, *assignee_expr_id, *value_expr_id)
+            }
+            ExprKind::AssignField(record_expr_id, field, value_expr_id) => {
+                self.analyze_expr_assign_field(*record_expr_id, field, *value_expr_id)
+            }
             ExprKind::AssignIndex(array_expr_id, index_expr_id, replacement_value_expr_id) => self
                 .analyze_expr_assign_index(
                     *array_expr_id,
                     *index_expr_id,
                     *replacement_value_expr_id,
                 ),
-            ExprKind::BinOp(_, lhs_expr_id, rhs_expr_id) => {
-                self.analyze_expr_bin_op(*lhs_expr_id, *rhs_expr_id, &expr.ty)
+            ExprKind::BinOp(op, lhs_expr_id, rhs_expr_id) => {
+                self.analyze_expr_bin_op(*op, *lhs_expr_id, *rhs_expr_id, &expr.ty)
             }
             ExprKind::Block(block_id) => self.analyze_expr_block(*block_id),
             ExprKind::Call(callee_expr_id, args_expr_id) => {
@@ -1511,8 +2159,8 @@ impl<'a> Visitor<'a> for Analyzer<'a> {
             }
             ExprKind::Closure(_, _) => Self::analyze_expr_closure(&expr.ty),
             ExprKind::Fail(msg_expr_id) => self.analyze_expr_fail(*msg_expr_id),
-            ExprKind::Field(record_expr_id, _) => {
-                self.analyze_expr_field(*record_expr_id, &expr.ty)
+            ExprKind::Field(record_expr_id, field) => {
+                self.analyze_expr_field(*record_expr_id, field, &expr.ty)
             }
             ExprKind::Hole | ExprKind::Lit(_) => {
                 // Hole and literal expressions are purely classical.
@@ -1645,7 +2293,7 @@ impl<'a> Visitor<'a> for Analyzer<'a> {
 
                 // The statement's compute kind is the same as the expression's compute kind.
                 let application_instance = self.get_current_application_instance();
-                *application_instance.get_expr_compute_kind(*expr_id)
+                application_instance.get_expr_compute_kind(*expr_id).clone()
             }
             StmtKind::Semi(expr_id) => {
                 // Visit the expression to determine its compute kind.
@@ -1653,11 +2301,14 @@ impl<'a> Visitor<'a> for Analyzer<'a> {
 
                 // Use the expression compute kind to construct the statement compute kind, using only the expression
                 // runtime features since the value kind is meaningless for semicolon statements.
+                // The runtime features must be kept even though the value is discarded because the expression can
+                // still have side effects (e.g. a measurement whose result is discarded still collapses the state).
                 let application_instance = self.get_current_application_instance();
-                let expr_compute_kind = *application_instance.get_expr_compute_kind(*expr_id);
+                let expr_compute_kind =
+                    application_instance.get_expr_compute_kind(*expr_id).clone();
                 ComputeKind::Classical.aggregate_runtime_features(
-                    expr_compute_kind,
-                    ValueKind::Element(RuntimeKind::Static),
+                    &expr_compute_kind,
+                    &ValueKind::Element(RuntimeKind::Static),
                 )
             }
             StmtKind::Local(mutability, pat_id, value_expr_id) => {
@@ -1670,10 +2321,12 @@ impl<'a> Visitor<'a> for Analyzer<'a> {
                 // Use the expression compute kind to construct the statement compute kind, using only the expression
                 // runtime features since the value kind is meaningless for local (binding) statements.
                 let application_instance = self.get_current_application_instance();
-                let expr_compute_kind = *application_instance.get_expr_compute_kind(*value_expr_id);
+                let expr_compute_kind = application_instance
+                    .get_expr_compute_kind(*value_expr_id)
+                    .clone();
                 ComputeKind::Classical.aggregate_runtime_features(
-                    expr_compute_kind,
-                    ValueKind::Element(RuntimeKind::Static),
+                    &expr_compute_kind,
+                    &ValueKind::Element(RuntimeKind::Static),
                 )
             }
             StmtKind::Item(_) => {
@@ -1717,6 +2370,7 @@ impl AnalysisContext {
 struct TopLevelContext {
     pub package_id: PackageId,
     builder: GeneratorSetsBuilder,
+    is_standalone: bool,
 }
 
 impl TopLevelContext {
@@ -1726,6 +2380,14 @@ impl TopLevelContext {
         Self {
             package_id,
             builder,
+            is_standalone: false,
+        }
+    }
+
+    fn new_standalone(package_id: PackageId) -> Self {
+        Self {
+            is_standalone: true,
+            ..Self::new(package_id)
         }
     }
 
@@ -1839,6 +2501,31 @@ impl SpecContext {
     }
 }
 
+fn derive_debug_only_intrinsic_application_generator_set(
+    callable_context: &CallableContext,
+) -> ApplicationGeneratorSet {
+    // Debug-only intrinsics have no effect on the program state, so binding their parameters to dynamic values does
+    // not use any runtime feature.
+    let dynamic_param_applications = callable_context
+        .input_params
+        .iter()
+        .map(|param| match &param.ty {
+            Ty::Array(_) => ParamApplication::Array(ArrayParamApplication {
+                static_content_dynamic_size: ComputeKind::Classical,
+                dynamic_content_static_size: ComputeKind::Classical,
+                dynamic_content_dynamic_size: ComputeKind::Classical,
+            }),
+            _ => ParamApplication::Element(ComputeKind::Classical),
+        })
+        .collect();
+
+    ApplicationGeneratorSet {
+        inherent: ComputeKind::Classical,
+        dynamic_param_applications,
+        from_cycle_assumption: false,
+    }
+}
+
 fn derive_intrinsic_function_application_generator_set(
     callable_context: &CallableContext,
 ) -> ApplicationGeneratorSet {
@@ -1851,22 +2538,29 @@ fn derive_intrinsic_function_application_generator_set(
         // For intrinsic functions, we assume any parameter can contribute to the output, so if any parameter is dynamic
         // the output of the function is dynamic.
         // When a parameter is bound to a dynamic value, its type contributes to the runtime features used by the
-        // function application.
+        // function application. Numeric conversions (e.g. `Truncate`) are the exception, since the dynamic value they
+        // produce is of a different type than the one of the parameter.
+        let features_ty = if is_numeric_conversion(&param.ty, &callable_context.output_type) {
+            &callable_context.output_type
+        } else {
+            &param.ty
+        };
         let runtime_features = derive_runtime_features_for_value_kind_associated_to_type(
-            ValueKind::new_dynamic_from_type(&param.ty),
-            &param.ty,
+            &ValueKind::new_dynamic_from_type(features_ty),
+            features_ty,
         );
         let value_kind = ValueKind::new_dynamic_from_type(&callable_context.output_type);
         let param_compute_kind = ComputeKind::Quantum(QuantumProperties {
             runtime_features,
+            extended_features: ExtendedFeatures::empty(),
             value_kind,
         });
 
         // Create a parameter application depending on the parameter type.
         let param_application = match &param.ty {
             Ty::Array(_) => ParamApplication::Array(ArrayParamApplication {
-                static_content_dynamic_size: param_compute_kind,
-                dynamic_content_static_size: param_compute_kind,
+                static_content_dynamic_size: param_compute_kind.clone(),
+                dynamic_content_static_size: param_compute_kind.clone(),
                 dynamic_content_dynamic_size: param_compute_kind,
             }),
             _ => ParamApplication::Element(param_compute_kind),
@@ -1878,6 +2572,7 @@ fn derive_intrinsic_function_application_generator_set(
         // Functions are inherently classical.
         inherent: ComputeKind::Classical,
         dynamic_param_applications,
+        from_cycle_assumption: false,
     }
 }
 
@@ -1898,6 +2593,7 @@ fn derive_instrinsic_operation_application_generator_set(
     // The compute kind of intrinsic operations is always quantum.
     let inherent_compute_kind = ComputeKind::Quantum(QuantumProperties {
         runtime_features: RuntimeFeatureFlags::empty(),
+        extended_features: ExtendedFeatures::empty(),
         value_kind,
     });
 
@@ -1910,20 +2606,21 @@ fn derive_instrinsic_operation_application_generator_set(
         // When a parameter is bound to a dynamic value, its type contributes to the runtime features used by the
         // operation application.
         let runtime_features = derive_runtime_features_for_value_kind_associated_to_type(
-            ValueKind::new_dynamic_from_type(&param.ty),
+            &ValueKind::new_dynamic_from_type(&param.ty),
             &param.ty,
         );
         let value_kind = ValueKind::new_dynamic_from_type(&callable_context.output_type);
         let param_compute_kind = ComputeKind::Quantum(QuantumProperties {
             runtime_features,
+            extended_features: ExtendedFeatures::empty(),
             value_kind,
         });
 
         // Create a parameter application depending on the parameter type.
         let param_application = match &param.ty {
             Ty::Array(_) => ParamApplication::Array(ArrayParamApplication {
-                static_content_dynamic_size: param_compute_kind,
-                dynamic_content_static_size: param_compute_kind,
+                static_content_dynamic_size: param_compute_kind.clone(),
+                dynamic_content_static_size: param_compute_kind.clone(),
                 dynamic_content_dynamic_size: param_compute_kind,
             }),
             _ => ParamApplication::Element(param_compute_kind),
@@ -1934,20 +2631,36 @@ fn derive_instrinsic_operation_application_generator_set(
     ApplicationGeneratorSet {
         inherent: inherent_compute_kind,
         dynamic_param_applications,
+        from_cycle_assumption: false,
     }
 }
 
 #[allow(clippy::too_many_lines)]
 fn derive_runtime_features_for_value_kind_associated_to_type(
-    value_kind: ValueKind,
+    value_kind: &ValueKind,
     ty: &Ty,
 ) -> RuntimeFeatureFlags {
     fn derive_runtime_features_for_value_kind_associated_to_array(
-        value_kind: ValueKind,
+        value_kind: &ValueKind,
         content_type: &Ty,
     ) -> RuntimeFeatureFlags {
-        let ValueKind::Array(content_runtime_kind, size_runtime_kind) = value_kind else {
-            panic!("expected array variant of value kind");
+        let (content_runtime_kind, size_runtime_kind) = match value_kind {
+            ValueKind::Array(content_runtime_kind, size_runtime_kind) => {
+                (content_runtime_kind, size_runtime_kind)
+            }
+            // The content of a nested array is made up of arrays, so its runtime features are derived recursively.
+            ValueKind::NestedArray(content_value_kind, size_runtime_kind) => {
+                let mut runtime_features =
+                    derive_runtime_features_for_value_kind_associated_to_type(
+                        content_value_kind,
+                        content_type,
+                    );
+                if matches!(size_runtime_kind, RuntimeKind::Dynamic) {
+                    runtime_features |= RuntimeFeatureFlags::UseOfDynamicallySizedArray;
+                }
+                return runtime_features;
+            }
+            _ => panic!("expected array variant of value kind"),
         };
 
         let mut runtime_features = RuntimeFeatureFlags::empty();
@@ -1961,7 +2674,7 @@ fn derive_runtime_features_for_value_kind_associated_to_type(
         if matches!(content_runtime_kind, RuntimeKind::Dynamic) {
             let content_value_kind = ValueKind::new_dynamic_from_type(content_type);
             runtime_features |= derive_runtime_features_for_value_kind_associated_to_type(
-                content_value_kind,
+                &content_value_kind,
                 content_type,
             );
         }
@@ -1970,7 +2683,7 @@ fn derive_runtime_features_for_value_kind_associated_to_type(
     }
 
     fn derive_runtime_features_for_value_kind_associated_to_arrow(
-        value_kind: ValueKind,
+        value_kind: &ValueKind,
         arrow: &Arrow,
     ) -> RuntimeFeatureFlags {
         let ValueKind::Element(runtime_kind) = value_kind else {
@@ -1988,7 +2701,7 @@ fn derive_runtime_features_for_value_kind_associated_to_type(
     }
 
     fn derive_runtime_features_for_value_kind_associated_to_primitive_type(
-        value_kind: ValueKind,
+        value_kind: &ValueKind,
         prim: Prim,
     ) -> RuntimeFeatureFlags {
         let ValueKind::Element(runtime_kind) = value_kind else {
@@ -2016,30 +2729,42 @@ fn derive_runtime_features_for_value_kind_associated_to_type(
     }
 
     fn derive_runtime_features_for_value_kind_associated_to_primitive_tuple(
-        value_kind: ValueKind,
+        value_kind: &ValueKind,
         element_types: &Vec<Ty>,
     ) -> RuntimeFeatureFlags {
-        let ValueKind::Element(runtime_kind) = value_kind else {
-            panic!("expected element variant of value kind");
-        };
-
-        if matches!(runtime_kind, RuntimeKind::Static) {
-            return RuntimeFeatureFlags::empty();
-        }
-
         let mut runtime_features = RuntimeFeatureFlags::empty();
-        for element_type in element_types {
-            let element_value_kind = ValueKind::new_dynamic_from_type(element_type);
-            runtime_features |= derive_runtime_features_for_value_kind_associated_to_type(
-                element_value_kind,
-                element_type,
-            );
+        match value_kind {
+            // When the value kind of each element is known, the runtime features are derived element by element.
+            ValueKind::Tuple(element_value_kinds) => {
+                assert!(element_value_kinds.len() == element_types.len());
+                for (element_value_kind, element_type) in
+                    element_value_kinds.iter().zip(element_types.iter())
+                {
+                    runtime_features |= derive_runtime_features_for_value_kind_associated_to_type(
+                        element_value_kind,
+                        element_type,
+                    );
+                }
+            }
+            ValueKind::Element(RuntimeKind::Dynamic) => {
+                for element_type in element_types {
+                    let element_value_kind = ValueKind::new_dynamic_from_type(element_type);
+                    runtime_features |= derive_runtime_features_for_value_kind_associated_to_type(
+                        &element_value_kind,
+                        element_type,
+                    );
+                }
+            }
+            ValueKind::Element(RuntimeKind::Static) => {}
+            ValueKind::Array(..) | ValueKind::NestedArray(..) => {
+                panic!("expected element or tuple variant of value kind")
+            }
         }
         runtime_features
     }
 
     fn derive_runtime_features_for_value_kind_associated_to_udt(
-        value_kind: ValueKind,
+        value_kind: &ValueKind,
     ) -> RuntimeFeatureFlags {
         let ValueKind::Element(runtime_kind) = value_kind else {
             panic!("expected element variant of value kind");
@@ -2075,6 +2800,67 @@ fn derive_runtime_features_for_value_kind_associated_to_type(
     }
 }
 
+/// Finds the callable with the given name in the `Microsoft.Quantum.Intrinsic` namespace of any package in the store.
+fn find_intrinsic_callable(package_store: &PackageStore, name: &str) -> Option<StoreItemId> {
+    package_store.iter().find_map(|(package_id, package)| {
+        let is_named_callable = |item_id: LocalItemId| {
+            package.items.get(item_id).is_some_and(|item| {
+                matches!(&item.kind, ItemKind::Callable(decl) if decl.name.name.as_ref() == name)
+            })
+        };
+        package.items.values().find_map(|item| match &item.kind {
+            ItemKind::Namespace(ident, items)
+                if ident.name.as_ref() == "Microsoft.Quantum.Intrinsic" =>
+            {
+                items
+                    .iter()
+                    .copied()
+                    .find(|item_id| is_named_callable(*item_id))
+                    .map(|item_id| StoreItemId::from((package_id, item_id)))
+            }
+            _ => None,
+        })
+    })
+}
+
+fn is_numeric_conversion(input_type: &Ty, output_type: &Ty) -> bool {
+    match (input_type, output_type) {
+        (
+            Ty::Prim(input_prim @ (Prim::Int | Prim::BigInt | Prim::Double)),
+            Ty::Prim(output_prim @ (Prim::Int | Prim::BigInt | Prim::Double)),
+        ) => input_prim != output_prim,
+        _ => false,
+    }
+}
+
+/// Whether the binary operator computes a numeric value out of its operands, which includes the bitwise and shift
+/// operators along with the arithmetic ones.
+fn derive_runtime_features_for_dynamic_arithmetic(expr_type: &Ty) -> RuntimeFeatureFlags {
+    if matches!(expr_type, Ty::Prim(Prim::Double)) {
+        RuntimeFeatureFlags::DynamicOperandInDoubleArithmetic
+    } else {
+        RuntimeFeatureFlags::DynamicOperandInIntArithmetic
+    }
+}
+
+fn is_numeric_bin_op(op: BinOp, expr_type: &Ty) -> bool {
+    matches!(expr_type, Ty::Prim(Prim::Int | Prim::BigInt | Prim::Double))
+        && matches!(
+            op,
+            BinOp::Add
+                | BinOp::AndB
+                | BinOp::Div
+                | BinOp::Exp
+                | BinOp::Mod
+                | BinOp::Mul
+                | BinOp::OrB
+                | BinOp::Shl
+                | BinOp::Shr
+                | BinOp::Sub
+                | BinOp::XorB
+        )
+}
+
 fn derive_specialization_controls(
     spec_decl: &SpecDecl,
     pats: &IndexMap<PatId, Pat>,
@@ -2,14 +2,14 @@
 // Licensed under the MIT License.
 
 use crate::common::{
-    derive_callable_input_params, initialize_locals_map, try_resolve_callee, FunctorAppExt, Local,
-    LocalKind, LocalSpecId,
+    derive_callable_input_params, get_spec_decl, initialize_locals_map, try_resolve_callee,
+    FunctorAppExt, Local, LocalKind, LocalSpecId, LocalsLookup,
 };
 use qsc_fir::{
     fir::{
         Block, BlockId, CallableDecl, CallableImpl, Expr, ExprId, ExprKind, Item, ItemKind,
-        LocalVarId, Mutability, Package, PackageId, PackageLookup, Pat, PatId, PatKind, SpecDecl,
-        Stmt, StmtId, StmtKind,
+        LocalItemId, LocalVarId, Mutability, Package, PackageId, PackageLookup, Pat, PatId,
+        PatKind, Res, SpecDecl, Stmt, StmtId, StmtKind,
     },
     ty::FunctorSetValue,
     visit::{walk_expr, Visitor},
@@ -41,6 +41,54 @@ impl<'a> CycleDetector<'a> {
         self.specializations_with_cycles.drain().collect()
     }
 
+    fn bind_closure_captures(&mut self, closure_spec_id: LocalSpecId, captures: &[LocalVarId]) {
+        // The captured locals are the leading input parameters of the lifted callable, so bind each of them to the
+        // expression the captured local is bound to in the enclosing specialization, if any.
+        let enclosing_locals_map = self
+            .specializations_locals
+            .get(self.stack.peak())
+            .expect("node map should exist");
+        let captured_exprs = captures
+            .iter()
+            .map(|var| {
+                enclosing_locals_map
+                    .find(*var)
+                    .and_then(|local| match local.kind {
+                        LocalKind::Immutable(expr_id) => Some(expr_id),
+                        _ => None,
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        let locals_map = self.get_or_init_locals_map(closure_spec_id);
+        for local in locals_map.values_mut() {
+            if let LocalKind::InputParam(index) = local.kind {
+                if let Some(Some(expr_id)) = captured_exprs.get(usize::from(index)) {
+                    local.kind = LocalKind::Immutable(*expr_id);
+                }
+            }
+        }
+    }
+
+    fn get_or_init_locals_map(
+        &mut self,
+        local_spec_id: LocalSpecId,
+    ) -> &mut FxHashMap<LocalVarId, Local> {
+        match self.specializations_locals.entry(local_spec_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let ItemKind::Callable(callable_decl) =
+                    &self.package.get_item(local_spec_id.callable).kind
+                else {
+                    panic!("item must be a callable");
+                };
+
+                let input_params = derive_callable_input_params(callable_decl, &self.package.pats);
+                entry.insert(initialize_locals_map(&input_params))
+            }
+        }
+    }
+
     fn map_pat_to_expr(&mut self, mutability: Mutability, pat_id: PatId, expr_id: ExprId) {
         let pat = self.get_pat(pat_id);
         match &pat.kind {
@@ -82,21 +130,7 @@ impl<'a> CycleDetector<'a> {
             return;
         };
 
-        let spec_decl = match local_spec_id.functor_set_value {
-            FunctorSetValue::Empty => &spec_impl.body,
-            FunctorSetValue::Adj => spec_impl
-                .adj
-                .as_ref()
-                .expect("adj specialization should exist"),
-            FunctorSetValue::Ctl => spec_impl
-                .ctl
-                .as_ref()
-                .expect("ctl specialization should exist"),
-            FunctorSetValue::CtlAdj => spec_impl
-                .ctl_adj
-                .as_ref()
-                .expect("ctl_adj specialization should exist"),
-        };
+        let spec_decl = get_spec_decl(spec_impl, local_spec_id.functor_set_value);
         self.walk_spec_decl(local_spec_id, spec_decl);
     }
 
@@ -111,25 +145,67 @@ impl<'a> CycleDetector<'a> {
             .get_mut(local_spec_id)
             .expect("node map should exist");
         let maybe_callee = try_resolve_callee(callee, self.package_id, self.package, locals_map);
-        if let Some(callee) = maybe_callee {
-            // We are not interested in visiting callables outside this package.
-            if callee.item.package != self.package_id {
-                return;
+        let Some(callee) = maybe_callee else {
+            // The callee might be a closure, in which case we walk the callable it was lifted to.
+            if let Some((captures, closure_item)) = self.try_resolve_closure_callee(callee) {
+                self.walk_closure(captures, closure_item);
             }
-            let item = self.package.get_item(callee.item.item);
-            match &item.kind {
-                ItemKind::Callable(callable_decl) => self.walk_callable_decl(
-                    (callee.item.item, callee.functor_app.functor_set_value()).into(),
-                    callable_decl,
-                ),
-                ItemKind::Namespace(_, _) => panic!("calls to namespaces are invalid"),
-                ItemKind::Ty(_, _) => {
-                    // Ignore "calls" to types.
-                }
+            return;
+        };
+
+        // We are not interested in visiting callables outside this package.
+        if callee.item.package != self.package_id {
+            return;
+        }
+        let item = self.package.get_item(callee.item.item);
+        match &item.kind {
+            ItemKind::Callable(callable_decl) => self.walk_callable_decl(
+                (callee.item.item, callee.functor_app.functor_set_value()).into(),
+                callable_decl,
+            ),
+            ItemKind::Namespace(_, _) => panic!("calls to namespaces are invalid"),
+            ItemKind::Ty(_, _) => {
+                // Ignore "calls" to types.
             }
         }
     }
 
+    fn try_resolve_closure_callee(
+        &self,
+        expr_id: ExprId,
+    ) -> Option<(&'a [LocalVarId], LocalItemId)> {
+        // This is a best effort attempt to resolve a closure callee that only follows immutable locals.
+        let expr = self.get_expr(expr_id);
+        match &expr.kind {
+            ExprKind::Closure(captures, closure_item) => Some((captures, *closure_item)),
+            ExprKind::Var(Res::Local(local_var_id), _) => self
+                .specializations_locals
+                .get(self.stack.peak())
+                .and_then(|locals_map| locals_map.find(*local_var_id))
+                .and_then(|local| match local.kind {
+                    LocalKind::Immutable(expr_id) => self.try_resolve_closure_callee(expr_id),
+                    _ => None,
+                }),
+            _ => None,
+        }
+    }
+
+    fn walk_closure(&mut self, captures: &[LocalVarId], closure_item: LocalItemId) {
+        let ItemKind::Callable(callable_decl) = &self.package.get_item(closure_item).kind else {
+            panic!("closure item must be a callable");
+        };
+        let CallableImpl::Spec(spec_impl) = &callable_decl.implementation else {
+            panic!("closure callable should not be intrinsic");
+        };
+
+        // Closures are lifted to callables that only have a body specialization.
+        let closure_spec_id = (closure_item, FunctorSetValue::Empty).into();
+        if !self.stack.contains(&closure_spec_id) {
+            self.bind_closure_captures(closure_spec_id, captures);
+        }
+        self.walk_spec_decl(closure_spec_id, &spec_impl.body);
+    }
+
     fn walk_spec_decl(&mut self, local_spec_id: LocalSpecId, spec_decl: &'a SpecDecl) {
         // If the specialization is already in the stack, it means the callable has a cycle.
         if self.stack.contains(&local_spec_id) {
@@ -138,17 +214,7 @@ impl<'a> CycleDetector<'a> {
         }
 
         // If this is the first time we are walking this specialization, create a node map for it.
-        if let Entry::Vacant(entry) = self.specializations_locals.entry(local_spec_id) {
-            let ItemKind::Callable(callable_decl) =
-                &self.package.get_item(local_spec_id.callable).kind
-            else {
-                panic!("item must be a callable");
-            };
-
-            let input_params = derive_callable_input_params(callable_decl, &self.package.pats);
-            let locals_map = initialize_locals_map(&input_params);
-            entry.insert(locals_map);
-        }
+        self.get_or_init_locals_map(local_spec_id);
 
         // Push the callable specialization to the stack, visit it and then pop it.
         self.stack.push(local_spec_id);
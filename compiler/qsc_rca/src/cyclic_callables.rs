@@ -2,7 +2,10 @@
 // Licensed under the MIT License.
 
 use crate::{
-    common::{derive_callable_input_params, InputParam, LocalSpecId},
+    common::{
+        contains_possibly_non_terminating_loop, derive_callable_input_params, get_spec_decl,
+        InputParam, LocalSpecId,
+    },
     cycle_detection::CycleDetector,
     scaffolding::InternalPackageStoreComputeProperties,
     ApplicationGeneratorSet, ArrayParamApplication, ComputeKind, ParamApplication,
@@ -13,7 +16,7 @@ use qsc_fir::{
         Block, BlockId, CallableDecl, CallableImpl, CallableKind, Expr, ExprId, Global, Item,
         Package, PackageId, PackageStore, PackageStoreLookup, Pat, PatId, SpecImpl, Stmt, StmtId,
     },
-    ty::{FunctorSetValue, Ty},
+    ty::Ty,
     visit::{self, Visitor},
 };
 
@@ -80,7 +83,7 @@ impl<'a> Analyzer<'a> {
         // operation.
         let input_params =
             derive_callable_input_params(callable, &self.package_store.get(package_id).pats);
-        let application_generator_set = match callable.kind {
+        let mut application_generator_set = match callable.kind {
             CallableKind::Function => {
                 Self::create_function_specialization_application_generator_set(
                     &input_params,
@@ -94,21 +97,58 @@ impl<'a> Analyzer<'a> {
         };
 
         // Find the specialization.
-        let spec_decl = match spec_id.functor_set_value {
-            FunctorSetValue::Empty => &spec_impl.body,
-            FunctorSetValue::Adj => spec_impl
-                .adj
-                .as_ref()
-                .expect("adj specialization should exist"),
-            FunctorSetValue::Ctl => spec_impl
-                .ctl
-                .as_ref()
-                .expect("ctl specialization should exist"),
-            FunctorSetValue::CtlAdj => spec_impl
-                .ctl_adj
-                .as_ref()
-                .expect("ctl_adj specializatiob should exist"),
-        };
+        let spec_decl = get_spec_decl(spec_impl, spec_id.functor_set_value);
+
+        // A loop that possibly never terminates is a property of the specialization regardless of its cycles.
+        if contains_possibly_non_terminating_loop(
+            spec_decl.block,
+            self.package_store.get(package_id),
+        ) {
+            match callable.kind {
+                // Functions are inherently classical, so the runtime feature only annotates the applications with
+                // dynamic arguments, which are already quantum.
+                CallableKind::Function => {
+                    let add_runtime_feature = |compute_kind: &mut ComputeKind| {
+                        if let ComputeKind::Quantum(quantum_properties) = compute_kind {
+                            quantum_properties.runtime_features |=
+                                RuntimeFeatureFlags::PossiblyNonTerminating;
+                        }
+                    };
+                    for param_application in
+                        &mut application_generator_set.dynamic_param_applications
+                    {
+                        match param_application {
+                            ParamApplication::Element(compute_kind) => {
+                                add_runtime_feature(compute_kind);
+                            }
+                            ParamApplication::Array(array_param_application) => {
+                                add_runtime_feature(
+                                    &mut array_param_application.static_content_dynamic_size,
+                                );
+                                add_runtime_feature(
+                                    &mut array_param_application.dynamic_content_static_size,
+                                );
+                                add_runtime_feature(
+                                    &mut array_param_application.dynamic_content_dynamic_size,
+                                );
+                            }
+                        }
+                    }
+                }
+                CallableKind::Operation => {
+                    let value_kind = ValueKind::new_static_from_type(&callable.output);
+                    application_generator_set.inherent = application_generator_set
+                        .inherent
+                        .aggregate_runtime_features(
+                            &ComputeKind::new_with_runtime_features(
+                                RuntimeFeatureFlags::PossiblyNonTerminating,
+                                value_kind.clone(),
+                            ),
+                            &value_kind,
+                        );
+                }
+            }
+        }
 
         // First visit the specialization to propagate the application generator set throughout all the relevant
         // sub-elements.
@@ -149,8 +189,8 @@ impl<'a> Analyzer<'a> {
             // Create a parameter application depending on the parameter type.
             let param_application = match &param.ty {
                 Ty::Array(_) => ParamApplication::Array(ArrayParamApplication {
-                    static_content_dynamic_size: param_compute_kind,
-                    dynamic_content_static_size: param_compute_kind,
+                    static_content_dynamic_size: param_compute_kind.clone(),
+                    dynamic_content_static_size: param_compute_kind.clone(),
                     dynamic_content_dynamic_size: param_compute_kind,
                 }),
                 _ => ParamApplication::Element(param_compute_kind),
@@ -162,6 +202,7 @@ impl<'a> Analyzer<'a> {
             // Functions are inherently classically pure.
             inherent: ComputeKind::Classical,
             dynamic_param_applications,
+            from_cycle_assumption: true,
         }
     }
 
@@ -284,11 +325,11 @@ fn create_operation_specialization_application_generator_set(
         // Create a parameter application depending on the parameter type.
         let param_application = match &param.ty {
             Ty::Array(_) => ParamApplication::Array(ArrayParamApplication {
-                static_content_dynamic_size: inherent_compute_kind,
-                dynamic_content_static_size: inherent_compute_kind,
-                dynamic_content_dynamic_size: inherent_compute_kind,
+                static_content_dynamic_size: inherent_compute_kind.clone(),
+                dynamic_content_static_size: inherent_compute_kind.clone(),
+                dynamic_content_dynamic_size: inherent_compute_kind.clone(),
             }),
-            _ => ParamApplication::Element(inherent_compute_kind),
+            _ => ParamApplication::Element(inherent_compute_kind.clone()),
         };
         dynamic_param_applications.push(param_application);
     }
@@ -296,5 +337,6 @@ fn create_operation_specialization_application_generator_set(
     ApplicationGeneratorSet {
         inherent: inherent_compute_kind,
         dynamic_param_applications,
+        from_cycle_assumption: true,
     }
 }
@@ -15,24 +15,32 @@ mod cyclic_callables;
 mod overrider;
 mod scaffolding;
 
-use crate::common::set_indentation;
+use crate::common::{set_indentation, try_resolve_callee, Local};
 use bitflags::bitflags;
 use indenter::indented;
-use qsc_data_structures::index_map::{IndexMap, Iter};
+use qsc_data_structures::{
+    index_map::{IndexMap, Iter},
+    span::Span,
+};
 use qsc_fir::{
     fir::{
-        BlockId, ExprId, LocalItemId, PackageId, StmtId, StoreBlockId, StoreExprId, StoreItemId,
-        StoreStmtId,
+        Attr, BlockId, ExprId, ExprKind, Global, ItemKind, LocalItemId, LocalVarId, PackageId,
+        PackageLookup, PackageStore, PackageStoreLookup, StmtId, StoreBlockId, StoreExprId,
+        StoreItemId, StoreStmtId,
     },
-    ty::Ty,
+    ty::{FunctorSetValue, Ty},
 };
 use qsc_frontend::compile::RuntimeCapabilityFlags;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::json;
 use std::{
     cmp::Ord,
+    collections::{BTreeMap, BTreeSet},
     fmt::{self, Debug, Display, Formatter, Write},
 };
 
-pub use crate::analyzer::Analyzer;
+pub use crate::analyzer::{Analyzer, DebugOnlyIntrinsicsHook, IntrinsicFeaturesHook};
+pub use crate::common::{GlobalSpecId, InputParamIndex};
 
 /// A trait to look for the compute properties of elements in a package store.
 pub trait ComputePropertiesLookup {
@@ -136,6 +144,272 @@ impl PackageStoreComputeProperties {
     pub fn iter(&self) -> Iter<PackageId, PackageComputeProperties> {
         self.0.iter()
     }
+
+    /// Whether two callables have identical capability profiles, which means that all their specializations use the
+    /// same runtime features both inherently and when any of their parameters is bound to a dynamic value.
+    #[must_use]
+    pub fn same_profile(&self, a: StoreItemId, b: StoreItemId) -> bool {
+        let (
+            ItemComputeProperties::Callable(a_compute_properties),
+            ItemComputeProperties::Callable(b_compute_properties),
+        ) = (self.get_item(a), self.get_item(b))
+        else {
+            return false;
+        };
+
+        let specializations = |compute_properties: &CallableComputeProperties| {
+            [
+                Some(compute_properties.body.runtime_features_profile()),
+                compute_properties
+                    .adj
+                    .as_ref()
+                    .map(ApplicationGeneratorSet::runtime_features_profile),
+                compute_properties
+                    .ctl
+                    .as_ref()
+                    .map(ApplicationGeneratorSet::runtime_features_profile),
+                compute_properties
+                    .ctl_adj
+                    .as_ref()
+                    .map(ApplicationGeneratorSet::runtime_features_profile),
+            ]
+        };
+        specializations(a_compute_properties) == specializations(b_compute_properties)
+    }
+
+    /// Verifies that every block in the package store has an application generator set, which is the case after all
+    /// the packages in the store have been analyzed. Returns the IDs of the blocks that are missing one otherwise.
+    pub fn verify_completeness(&self, package_store: &PackageStore) -> Result<(), Vec<BlockId>> {
+        let mut missing_blocks = Vec::new();
+        for (package_id, package) in package_store {
+            let package_compute_properties = self.0.get(package_id);
+            for (block_id, _) in &package.blocks {
+                if package_compute_properties
+                    .and_then(|package_compute_properties| {
+                        package_compute_properties.blocks.get(block_id)
+                    })
+                    .is_none()
+                {
+                    missing_blocks.push(block_id);
+                }
+            }
+        }
+
+        if missing_blocks.is_empty() {
+            Ok(())
+        } else {
+            Err(missing_blocks)
+        }
+    }
+
+    /// Checks that no callable in the store uses more distinct runtime features across its specializations than the
+    /// specified budget, including the ones used when its parameters are bound to dynamic values. Returns the IDs of the
+    /// callables that exceed it otherwise.
+    pub fn check_feature_budget(&self, max: u32) -> Result<(), Vec<StoreItemId>> {
+        let mut over_budget_callables = Vec::new();
+        for (package_id, package_compute_properties) in self {
+            for (item_id, item_compute_properties) in package_compute_properties.items.iter() {
+                let ItemComputeProperties::Callable(callable_compute_properties) =
+                    item_compute_properties
+                else {
+                    continue;
+                };
+
+                let runtime_features = [
+                    Some(&callable_compute_properties.body),
+                    callable_compute_properties.adj.as_ref(),
+                    callable_compute_properties.ctl.as_ref(),
+                    callable_compute_properties.ctl_adj.as_ref(),
+                ]
+                .into_iter()
+                .flatten()
+                .flat_map(ApplicationGeneratorSet::runtime_features_profile)
+                .fold(RuntimeFeatureFlags::empty(), |acc, features| acc | features);
+                if runtime_features.bits().count_ones() > max {
+                    over_budget_callables.push((package_id, item_id).into());
+                }
+            }
+        }
+
+        if over_budget_callables.is_empty() {
+            Ok(())
+        } else {
+            Err(over_budget_callables)
+        }
+    }
+
+    /// Gets the runtime features reachable from an entry callable, which are the ones its body uses when it is
+    /// called with static arguments. These already include the runtime features of the callables it calls, since the
+    /// compute kind of a call aggregates the ones of the applied callee. Items that are not callables in the store
+    /// reach no runtime features.
+    #[must_use]
+    pub fn reachable_capabilities(
+        &self,
+        entry: StoreItemId,
+        store: &PackageStore,
+    ) -> RuntimeFeatureFlags {
+        let Some(Global::Callable(_)) = store.get_global(entry) else {
+            return RuntimeFeatureFlags::empty();
+        };
+        let ItemComputeProperties::Callable(callable_compute_properties) = self.get_item(entry)
+        else {
+            panic!("item should be a callable");
+        };
+        callable_compute_properties.body.inherent.runtime_features()
+    }
+
+    /// Gets the union of the runtime features reachable from each of the specified entry callables, which is the
+    /// combined requirement of a library with multiple entry points.
+    #[must_use]
+    pub fn union_reachable(
+        &self,
+        entries: &[StoreItemId],
+        store: &PackageStore,
+    ) -> RuntimeFeatureFlags {
+        entries
+            .iter()
+            .fold(RuntimeFeatureFlags::empty(), |acc, entry| {
+                acc | self.reachable_capabilities(*entry, store)
+            })
+    }
+
+    /// Gets the callables that are always called with static arguments, which are the ones with at least one call site
+    /// in the store and whose call sites all pass arguments that are static. The arguments of a call site are only
+    /// considered static when they remain static regardless of the value kinds the enclosing callable is called with,
+    /// so a callable that is only called by forwarding the parameters of its caller is not reported.
+    /// Call sites whose callee cannot be resolved at compile time are not considered.
+    #[must_use]
+    pub fn always_static_call_sites(&self, store: &PackageStore) -> Vec<StoreItemId> {
+        let mut callables_with_static_args = FxHashMap::<(PackageId, LocalItemId), bool>::default();
+        let locals_map = FxHashMap::<LocalVarId, Local>::default();
+        for (package_id, package) in store {
+            for (_, expr) in &package.exprs {
+                let ExprKind::Call(callee_expr_id, args_expr_id) = &expr.kind else {
+                    continue;
+                };
+                let Some(callee) =
+                    try_resolve_callee(*callee_expr_id, package_id, package, &locals_map)
+                else {
+                    continue;
+                };
+                let Some(Global::Callable(_)) = store.get_global(callee.item) else {
+                    continue;
+                };
+
+                let are_args_static = self
+                    .0
+                    .get(package_id)
+                    .and_then(|package_compute_properties| {
+                        package_compute_properties.exprs.get(*args_expr_id)
+                    })
+                    .is_some_and(|args_generator_set| {
+                        !args_generator_set.inherent.is_dynamic()
+                            && args_generator_set.dynamic_param_applications.iter().all(
+                                |param_application| match param_application {
+                                    ParamApplication::Element(compute_kind) => {
+                                        !compute_kind.is_dynamic()
+                                    }
+                                    ParamApplication::Array(array_param_application) => {
+                                        !array_param_application
+                                            .static_content_dynamic_size
+                                            .is_dynamic()
+                                            && !array_param_application
+                                                .dynamic_content_static_size
+                                                .is_dynamic()
+                                            && !array_param_application
+                                                .dynamic_content_dynamic_size
+                                                .is_dynamic()
+                                    }
+                                },
+                            )
+                    });
+                *callables_with_static_args
+                    .entry((callee.item.package, callee.item.item))
+                    .or_insert(true) &= are_args_static;
+            }
+        }
+
+        let mut callables = callables_with_static_args
+            .into_iter()
+            .filter_map(|(id, are_args_static)| are_args_static.then_some(id))
+            .collect::<Vec<_>>();
+        callables.sort_unstable();
+        callables.into_iter().map(StoreItemId::from).collect()
+    }
+
+    /// Whether the program uses mid-circuit measurements, i.e. the body of an entry point callable allocates results
+    /// within a dynamic scope or uses a value derived from a measurement result (e.g. by branching on it). Only what is
+    /// reachable from the entry points is considered, since library specializations that are never called (e.g. the
+    /// controlled specializations in the standard library) do not affect what the program requires. Packages without
+    /// an entry point are considered through their top-level statements instead.
+    /// The runtime features of an entry point body already include the ones of the callables it calls.
+    #[must_use]
+    pub fn uses_mid_circuit_measurement(&self, store: &PackageStore) -> bool {
+        let mid_circuit_measurement_features = RuntimeFeatureFlags::DynamicResultAllocation
+            | RuntimeFeatureFlags::ForwardBranchingOnDynamicValue
+            | RuntimeFeatureFlags::UseOfDynamicBool;
+        self.iter().any(|(package_id, package_compute_properties)| {
+            let runtime_features = match package_compute_properties.entry_point(store, package_id) {
+                Some(entry_point) => self.reachable_capabilities(entry_point, store),
+                None => package_compute_properties.top_level_runtime_features(store, package_id),
+            };
+            runtime_features.intersects(mid_circuit_measurement_features)
+        })
+    }
+
+    /// Gets the largest number of dynamic parameter applications of any callable specialization in the store, which
+    /// bounds the number of applications the analysis derives for a single specialization.
+    #[must_use]
+    pub fn max_dynamic_param_count(&self) -> usize {
+        self.iter()
+            .map(|(_, package_compute_properties)| {
+                package_compute_properties.max_dynamic_param_count()
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Gets all the specializations that are quantum sources, which are the ones whose inherent compute kind is quantum
+    /// with a dynamic value kind, meaning that they produce dynamic values on their own regardless of their arguments.
+    #[must_use]
+    pub fn quantum_source_specializations(&self) -> Vec<GlobalSpecId> {
+        let mut quantum_source_specializations = Vec::new();
+        for (package_id, package_compute_properties) in self {
+            for (item_id, item_compute_properties) in package_compute_properties.items.iter() {
+                let ItemComputeProperties::Callable(callable_compute_properties) =
+                    item_compute_properties
+                else {
+                    continue;
+                };
+
+                let specializations = [
+                    (
+                        FunctorSetValue::Empty,
+                        Some(&callable_compute_properties.body),
+                    ),
+                    (
+                        FunctorSetValue::Adj,
+                        callable_compute_properties.adj.as_ref(),
+                    ),
+                    (
+                        FunctorSetValue::Ctl,
+                        callable_compute_properties.ctl.as_ref(),
+                    ),
+                    (
+                        FunctorSetValue::CtlAdj,
+                        callable_compute_properties.ctl_adj.as_ref(),
+                    ),
+                ];
+                for (functor_set_value, spec) in specializations {
+                    if spec.is_some_and(|spec| spec.inherent.is_dynamic()) {
+                        quantum_source_specializations
+                            .push(((package_id, item_id).into(), functor_set_value).into());
+                    }
+                }
+            }
+        }
+        quantum_source_specializations
+    }
 }
 
 /// The compute properties of a package.
@@ -149,6 +423,8 @@ pub struct PackageComputeProperties {
     pub stmts: IndexMap<StmtId, ApplicationGeneratorSet>,
     /// The application generator sets of the package expressions.
     pub exprs: IndexMap<ExprId, ApplicationGeneratorSet>,
+    /// The maximum depth of nested dynamic scopes across the specializations of each callable in the package.
+    pub dynamic_scope_depths: IndexMap<LocalItemId, usize>,
 }
 
 impl Default for PackageComputeProperties {
@@ -158,10 +434,48 @@ impl Default for PackageComputeProperties {
             blocks: IndexMap::new(),
             stmts: IndexMap::new(),
             exprs: IndexMap::new(),
+            dynamic_scope_depths: IndexMap::new(),
         }
     }
 }
 
+impl PackageComputeProperties {
+    /// Exports the compute properties of the expressions of the package with the specified ID as JSON for language
+    /// service consumption. Each source span covered by expressions gets an entry with whether any of those expressions
+    /// is dynamic and the names of the runtime features they use, sorted by span.
+    #[must_use]
+    pub fn to_lsp_json(&self, store: &PackageStore, package_id: PackageId) -> serde_json::Value {
+        let package = store.get(package_id);
+        let mut entries = BTreeMap::<Span, (bool, RuntimeFeatureFlags)>::new();
+        for (expr_id, application_generator_set) in self.exprs.iter() {
+            let span = package.get_expr(expr_id).span;
+            if span == Span::default() {
+                continue;
+            }
+            let compute_kind = &application_generator_set.inherent;
+            let (dynamic, features) = entries
+                .entry(span)
+                .or_insert((false, RuntimeFeatureFlags::empty()));
+            *dynamic |= compute_kind.is_dynamic();
+            *features |= compute_kind.runtime_features();
+        }
+        entries
+            .into_iter()
+            .map(|(span, (dynamic, features))| {
+                let features = features
+                    .iter_names()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>();
+                json!({
+                    "span": { "start": span.lo, "end": span.hi },
+                    "dynamic": dynamic,
+                    "features": features,
+                })
+            })
+            .collect()
+    }
+}
+
 impl Display for PackageComputeProperties {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let mut indent = set_indentation(indented(f), 0);
@@ -200,6 +514,7 @@ impl PackageComputeProperties {
         self.blocks.clear();
         self.stmts.clear();
         self.exprs.clear();
+        self.dynamic_scope_depths.clear();
     }
 
     #[must_use]
@@ -209,6 +524,13 @@ impl PackageComputeProperties {
             .expect("block compute properties not found")
     }
 
+    /// Gets the runtime features the block with the specified ID uses inherently, which is useful to highlight the
+    /// blocks that require capabilities.
+    #[must_use]
+    pub fn block_features(&self, block: BlockId) -> RuntimeFeatureFlags {
+        self.get_block(block).inherent.runtime_features()
+    }
+
     #[must_use]
     pub fn get_expr(&self, id: ExprId) -> &ApplicationGeneratorSet {
         self.exprs
@@ -229,13 +551,118 @@ impl PackageComputeProperties {
             .get(id)
             .expect("statement compute properties not found")
     }
+
+    /// Gets the ID of the callable attributed with `@EntryPoint()` in the package with the specified ID, if any, which
+    /// is the one that defines the capabilities the program requires.
+    #[must_use]
+    pub fn entry_point(&self, store: &PackageStore, package_id: PackageId) -> Option<StoreItemId> {
+        store
+            .get(package_id)
+            .items
+            .iter()
+            .find(|(item_id, item)| {
+                matches!(item.kind, ItemKind::Callable(_))
+                    && item.attrs.contains(&Attr::EntryPoint)
+                    && self.items.get(*item_id).is_some()
+            })
+            .map(|(item_id, _)| (package_id, item_id).into())
+    }
+
+    /// Gets the union of the runtime features of the top-level statements of the package with the specified ID, which
+    /// are the statements that do not belong to any block (e.g. the ones entered in an interactive session).
+    #[must_use]
+    pub fn top_level_runtime_features(
+        &self,
+        store: &PackageStore,
+        package_id: PackageId,
+    ) -> RuntimeFeatureFlags {
+        let package = store.get(package_id);
+        let block_stmts = package
+            .blocks
+            .values()
+            .flat_map(|block| block.stmts.iter().copied())
+            .collect::<FxHashSet<_>>();
+        package
+            .stmts
+            .iter()
+            .filter(|(stmt_id, _)| !block_stmts.contains(stmt_id))
+            .filter_map(|(stmt_id, _)| self.stmts.get(stmt_id))
+            .fold(
+                RuntimeFeatureFlags::empty(),
+                |acc, stmt_compute_properties| {
+                    acc | stmt_compute_properties.inherent.runtime_features()
+                },
+            )
+    }
+
+    /// Gets the runtime capabilities required by the body of the entry point callable of the package with the specified
+    /// ID, if the package has one.
+    #[must_use]
+    pub fn entry_point_capabilities(
+        &self,
+        store: &PackageStore,
+        package_id: PackageId,
+    ) -> Option<RuntimeCapabilityFlags> {
+        let entry_point = self.entry_point(store, package_id)?;
+        let ItemComputeProperties::Callable(callable_compute_properties) =
+            self.get_item(entry_point.item)
+        else {
+            panic!("entry point should be a callable");
+        };
+        Some(
+            callable_compute_properties
+                .body
+                .inherent
+                .runtime_features()
+                .runtime_capabilities(),
+        )
+    }
+
+    /// Gets the maximum number of dynamic scopes (e.g. `if` expressions with a dynamic condition) that are nested
+    /// within any specialization of the item. Items with no dynamic scopes have a depth of zero.
+    #[must_use]
+    pub fn max_dynamic_scope_depth(&self, item: StoreItemId) -> usize {
+        self.dynamic_scope_depths
+            .get(item.item)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Gets the largest number of dynamic parameter applications of any callable specialization in the package, which
+    /// is the number of parameters of its widest specialization. Packages with no callables have a count of zero.
+    #[must_use]
+    pub fn max_dynamic_param_count(&self) -> usize {
+        self.items
+            .iter()
+            .filter_map(
+                |(_, item_compute_properties)| match item_compute_properties {
+                    ItemComputeProperties::Callable(callable_compute_properties) => {
+                        Some(callable_compute_properties)
+                    }
+                    ItemComputeProperties::NonCallable => None,
+                },
+            )
+            .flat_map(|callable_compute_properties| {
+                [
+                    Some(&callable_compute_properties.body),
+                    callable_compute_properties.adj.as_ref(),
+                    callable_compute_properties.ctl.as_ref(),
+                    callable_compute_properties.ctl_adj.as_ref(),
+                ]
+                .into_iter()
+                .flatten()
+            })
+            .map(|spec| spec.dynamic_param_applications.len())
+            .max()
+            .unwrap_or_default()
+    }
 }
 
 /// The compute properties of an item.
 #[derive(Clone, Debug)]
 pub enum ItemComputeProperties {
     /// The compute properties of a callable.
-    Callable(CallableComputeProperties),
+    Callable(Box<CallableComputeProperties>),
     /// The compute properties of a non-callable (for completeness only).
     NonCallable,
 }
@@ -264,6 +691,25 @@ pub struct CallableComputeProperties {
     pub ctl_adj: Option<ApplicationGeneratorSet>,
 }
 
+impl CallableComputeProperties {
+    /// Summarizes the inherent runtime features of each specialization the callable has, keyed by the functor set
+    /// value that identifies the specialization.
+    #[must_use]
+    pub fn per_specialization_features(&self) -> BTreeMap<FunctorSetValue, RuntimeFeatureFlags> {
+        [
+            (FunctorSetValue::Empty, Some(&self.body)),
+            (FunctorSetValue::Adj, self.adj.as_ref()),
+            (FunctorSetValue::Ctl, self.ctl.as_ref()),
+            (FunctorSetValue::CtlAdj, self.ctl_adj.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(functor_set_value, spec)| {
+            spec.map(|spec| (functor_set_value, spec.inherent.runtime_features()))
+        })
+        .collect()
+    }
+}
+
 impl Display for CallableComputeProperties {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let mut indent = set_indentation(indented(f), 0);
@@ -296,6 +742,9 @@ pub struct ApplicationGeneratorSet {
     /// Each element in the vector represents the compute kind(s) of a call application when the parameter associated to
     /// the vector index is bound to a dynamic value.
     pub(crate) dynamic_param_applications: Vec<ParamApplication>,
+    /// Whether the compute properties were conservatively assumed because the program element belongs to a cyclic
+    /// callable, rather than derived through precise analysis.
+    pub(crate) from_cycle_assumption: bool,
 }
 
 impl Display for ApplicationGeneratorSet {
@@ -320,10 +769,179 @@ impl Display for ApplicationGeneratorSet {
 }
 
 impl ApplicationGeneratorSet {
+    /// Renders the compute kind of every combination of static and dynamic arguments as a table, with a column for
+    /// each parameter and a row for each combination.
     #[must_use]
-    pub fn generate_application_compute_kind(&self, args_value_kinds: &[ValueKind]) -> ComputeKind {
+    pub fn to_table_string(&self) -> String {
+        // Determine the value kinds each parameter can be bound to.
+        let param_value_kinds = self
+            .dynamic_param_applications
+            .iter()
+            .map(|param_application| match param_application {
+                ParamApplication::Element(_) => vec![
+                    ValueKind::Element(RuntimeKind::Static),
+                    ValueKind::Element(RuntimeKind::Dynamic),
+                ],
+                ParamApplication::Array(_) => vec![
+                    ValueKind::Array(RuntimeKind::Static, RuntimeKind::Static),
+                    ValueKind::Array(RuntimeKind::Static, RuntimeKind::Dynamic),
+                    ValueKind::Array(RuntimeKind::Dynamic, RuntimeKind::Static),
+                    ValueKind::Array(RuntimeKind::Dynamic, RuntimeKind::Dynamic),
+                ],
+            })
+            .collect::<Vec<_>>();
+
+        // Build the rows of the table by going through every combination of value kinds, where the last parameter
+        // varies the fastest.
+        let mut header = (0..param_value_kinds.len())
+            .map(|param_index| format!("[{param_index}]"))
+            .collect::<Vec<_>>();
+        header.push("compute_kind".to_string());
+        let mut rows = vec![header];
+        let mut combinations = vec![Vec::<ValueKind>::new()];
+        for value_kinds in &param_value_kinds {
+            combinations = combinations
+                .into_iter()
+                .flat_map(|combination| {
+                    value_kinds.iter().map(move |value_kind| {
+                        let mut combination = combination.clone();
+                        combination.push(value_kind.clone());
+                        combination
+                    })
+                })
+                .collect();
+        }
+        for args_value_kinds in combinations {
+            let compute_kind = self.apply(&args_value_kinds);
+            let mut row = args_value_kinds
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            row.push(match compute_kind {
+                ComputeKind::Classical => "Classical".to_string(),
+                ComputeKind::Quantum(quantum_properties) => format!(
+                    "Quantum: {:?}, {}",
+                    quantum_properties.runtime_features, quantum_properties.value_kind
+                ),
+            });
+            rows.push(row);
+        }
+
+        // Pad every column to the width of its widest cell.
+        let widths = (0..=param_value_kinds.len())
+            .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+            .collect::<Vec<_>>();
+        let mut table = String::new();
+        for row in rows {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>()
+                .join(" | ");
+            table.push_str(line.trim_end());
+            table.push('\n');
+        }
+        table
+    }
+
+    /// Renders the generator set on a single line that is easy to grep and parse in scripts, e.g.
+    /// `inherent=Q[]:Element(Static);p0=Q[UseOfDynamicQubit]:Element(Static)`. Array parameters get one entry per
+    /// dynamic combination, suffixed with `sd` (static content, dynamic size), `ds` or `dd`.
+    #[must_use]
+    pub fn to_compact_string(&self) -> String {
+        let mut entries = vec![format!("inherent={}", self.inherent.to_compact_string())];
+        for (param_index, param_application) in self.dynamic_param_applications.iter().enumerate() {
+            match param_application {
+                ParamApplication::Element(compute_kind) => {
+                    entries.push(format!(
+                        "p{param_index}={}",
+                        compute_kind.to_compact_string()
+                    ));
+                }
+                ParamApplication::Array(array_param_application) => entries.extend([
+                    format!(
+                        "p{param_index}.sd={}",
+                        array_param_application
+                            .static_content_dynamic_size
+                            .to_compact_string()
+                    ),
+                    format!(
+                        "p{param_index}.ds={}",
+                        array_param_application
+                            .dynamic_content_static_size
+                            .to_compact_string()
+                    ),
+                    format!(
+                        "p{param_index}.dd={}",
+                        array_param_application
+                            .dynamic_content_dynamic_size
+                            .to_compact_string()
+                    ),
+                ]),
+            }
+        }
+        entries.join(";")
+    }
+
+    /// The runtime features used inherently followed by the ones used when each parameter is bound to a dynamic value.
+    fn runtime_features_profile(&self) -> Vec<RuntimeFeatureFlags> {
+        let mut profile = vec![self.inherent.runtime_features()];
+        for param_application in &self.dynamic_param_applications {
+            match param_application {
+                ParamApplication::Element(compute_kind) => {
+                    profile.push(compute_kind.runtime_features());
+                }
+                ParamApplication::Array(array_param_application) => profile.extend([
+                    array_param_application
+                        .static_content_dynamic_size
+                        .runtime_features(),
+                    array_param_application
+                        .dynamic_content_static_size
+                        .runtime_features(),
+                    array_param_application
+                        .dynamic_content_dynamic_size
+                        .runtime_features(),
+                ]),
+            }
+        }
+        profile
+    }
+
+    /// Whether applying the program element never executes quantum instructions nor uses qubits, regardless of
+    /// whether its arguments are static or dynamic, which means it can be executed classically even if it is an
+    /// operation (e.g. an operation that only does arithmetic). The runtime features of dynamic arguments that are
+    /// not qubits only come from the caller, so they do not prevent the element from being effectively classical.
+    #[must_use]
+    pub fn is_effectively_classical(&self) -> bool {
+        let qubit_features = RuntimeFeatureFlags::UseOfDynamicQubit
+            | RuntimeFeatureFlags::DynamicResultAllocation
+            | RuntimeFeatureFlags::DynamicQubitRelease;
+        matches!(self.inherent, ComputeKind::Classical)
+            && self
+                .runtime_features_profile()
+                .iter()
+                .all(|runtime_features| !runtime_features.intersects(qubit_features))
+    }
+
+    /// Whether this generator set is the conservative assumption made for a cyclic callable instead of the result of
+    /// precise analysis.
+    #[must_use]
+    pub fn is_from_cycle_assumption(&self) -> bool {
+        self.from_cycle_assumption
+    }
+
+    /// Derives the compute kind of a particular application of the program element from the value kinds of the
+    /// arguments it is applied with, one per parameter. Since the generator set does not depend on the arguments, it
+    /// can be computed once and then applied cheaply to every call site.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of argument value kinds does not match the number of parameters.
+    #[must_use]
+    pub fn apply(&self, args_value_kinds: &[ValueKind]) -> ComputeKind {
         assert!(self.dynamic_param_applications.len() == args_value_kinds.len());
-        let mut compute_kind = self.inherent;
+        let mut compute_kind = self.inherent.clone();
         for (arg_value_kind, param_application) in args_value_kinds
             .iter()
             .zip(self.dynamic_param_applications.iter())
@@ -352,7 +970,7 @@ impl ApplicationGeneratorSet {
                     panic!("parameter application was expected to be an element variant");
                 };
 
-                compute_kind = compute_kind.aggregate(*param_compute_kind);
+                compute_kind = compute_kind.aggregate(param_compute_kind.clone());
             } else if let ValueKind::Array(content_runtime_value, size_runtime_value) =
                 mapped_value_kind
             {
@@ -364,13 +982,13 @@ impl ApplicationGeneratorSet {
                     // When both the content and the size are static, we can treat it as aggregating a classical element.
                     (RuntimeKind::Static, RuntimeKind::Static) => ComputeKind::Classical,
                     (RuntimeKind::Dynamic, RuntimeKind::Static) => {
-                        array_param_application.dynamic_content_static_size
+                        array_param_application.dynamic_content_static_size.clone()
                     }
                     (RuntimeKind::Static, RuntimeKind::Dynamic) => {
-                        array_param_application.static_content_dynamic_size
+                        array_param_application.static_content_dynamic_size.clone()
                     }
                     (RuntimeKind::Dynamic, RuntimeKind::Dynamic) => {
-                        array_param_application.dynamic_content_dynamic_size
+                        array_param_application.dynamic_content_dynamic_size.clone()
                     }
                 };
 
@@ -430,7 +1048,7 @@ impl Display for ArrayParamApplication {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum ComputeKind {
     Classical,
     Quantum(QuantumProperties),
@@ -453,10 +1071,42 @@ impl ComputeKind {
     ) -> Self {
         Self::Quantum(QuantumProperties {
             runtime_features,
+            extended_features: ExtendedFeatures::empty(),
             value_kind,
         })
     }
 
+    /// Renders the compute kind as `Classical` or as `Q[<features>]:<value kind>`, with the runtime features followed by
+    /// the extended features separated by `|`.
+    fn to_compact_string(&self) -> String {
+        match self {
+            Self::Classical => "Classical".to_string(),
+            Self::Quantum(quantum_properties) => {
+                let mut names = quantum_properties
+                    .runtime_features
+                    .iter_names()
+                    .map(|(name, _)| name.to_string())
+                    .collect::<Vec<_>>();
+                names.extend(
+                    quantum_properties
+                        .extended_features
+                        .names()
+                        .map(ToString::to_string),
+                );
+                format!("Q[{}]:{}", names.join("|"), quantum_properties.value_kind)
+            }
+        }
+    }
+
+    /// The runtime features of the compute kind, which are empty for classical compute kinds.
+    #[must_use]
+    pub fn runtime_features(&self) -> RuntimeFeatureFlags {
+        match self {
+            Self::Classical => RuntimeFeatureFlags::empty(),
+            Self::Quantum(quantum_properties) => quantum_properties.runtime_features,
+        }
+    }
+
     pub(crate) fn aggregate(self, value: Self) -> Self {
         let ComputeKind::Quantum(value_quantum_properties) = value else {
             // A classical compute kind has nothing to aggregate so just return self with no changes.
@@ -471,6 +1121,14 @@ impl ComputeKind {
             }
         };
 
+        // Determine the aggregated extended features.
+        let extended_features = match self {
+            Self::Classical => value_quantum_properties.extended_features,
+            Self::Quantum(ref self_quantum_properties) => self_quantum_properties
+                .extended_features
+                .union(&value_quantum_properties.extended_features),
+        };
+
         // Determine the aggregated value kind.
         let value_kind = match self {
             Self::Classical => value_quantum_properties.value_kind,
@@ -482,14 +1140,15 @@ impl ComputeKind {
         // Return the aggregated compute kind.
         ComputeKind::Quantum(QuantumProperties {
             runtime_features,
+            extended_features,
             value_kind,
         })
     }
 
     pub(crate) fn aggregate_runtime_features(
         self,
-        value: ComputeKind,
-        default_value_kind: ValueKind,
+        value: &ComputeKind,
+        default_value_kind: &ValueKind,
     ) -> Self {
         let Self::Quantum(value_quantum_properties) = value else {
             // A classical compute kind has nothing to aggregate so just return the self with no changes.
@@ -504,16 +1163,25 @@ impl ComputeKind {
             }
         };
 
+        // Extended features are aggregated the same way runtime features are.
+        let extended_features = match self {
+            Self::Classical => value_quantum_properties.extended_features.clone(),
+            Self::Quantum(ref self_quantum_properties) => self_quantum_properties
+                .extended_features
+                .union(&value_quantum_properties.extended_features),
+        };
+
         // Use the value kind equivalent from self.
         let value_kind = match self {
             // If self was classical, the aggregated value kind is all static.
-            Self::Classical => default_value_kind,
+            Self::Classical => default_value_kind.clone(),
             Self::Quantum(self_quantum_properties) => self_quantum_properties.value_kind,
         };
 
         // Return the aggregated compute kind.
         ComputeKind::Quantum(QuantumProperties {
             runtime_features,
+            extended_features,
             value_kind,
         })
     }
@@ -523,36 +1191,38 @@ impl ComputeKind {
             panic!("a value kind can only be aggregated to a compute kind of the quantum variant");
         };
 
-        quantum_properties.value_kind = quantum_properties.value_kind.aggregate(value);
+        quantum_properties.value_kind = quantum_properties.value_kind.clone().aggregate(value);
     }
 
-    pub(crate) fn is_dynamic(self) -> bool {
+    pub(crate) fn is_dynamic(&self) -> bool {
         match self {
             Self::Classical => false,
             Self::Quantum(quantum_properties) => quantum_properties.value_kind.is_dynamic(),
         }
     }
 
-    pub(crate) fn value_kind(self) -> Option<ValueKind> {
+    pub(crate) fn value_kind(&self) -> Option<ValueKind> {
         match self {
             Self::Classical => None,
-            Self::Quantum(quantum_properties) => Some(quantum_properties.value_kind),
+            Self::Quantum(quantum_properties) => Some(quantum_properties.value_kind.clone()),
         }
     }
 
-    pub(crate) fn value_kind_or_default(self, default: ValueKind) -> ValueKind {
+    pub(crate) fn value_kind_or_default(&self, default: ValueKind) -> ValueKind {
         match self {
             Self::Classical => default,
-            Self::Quantum(quantum_properties) => quantum_properties.value_kind,
+            Self::Quantum(quantum_properties) => quantum_properties.value_kind.clone(),
         }
     }
 }
 
 /// The quantum properties of a program element.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct QuantumProperties {
     /// The runtime features used by the program element.
     pub runtime_features: RuntimeFeatureFlags,
+    /// The vendor-specific runtime features used by the program element.
+    pub extended_features: ExtendedFeatures,
     /// The kind of value of the program element.
     pub(crate) value_kind: ValueKind,
 }
@@ -563,17 +1233,69 @@ impl Display for QuantumProperties {
         write!(indent, "QuantumProperties:",)?;
         indent = set_indentation(indent, 1);
         write!(indent, "\nruntime_features: {:?}", self.runtime_features)?;
+        if !self.extended_features.is_empty() {
+            write!(indent, "\nextended_features: {}", self.extended_features)?;
+        }
         write!(indent, "\nvalue_kind: {}", self.value_kind)?;
         Ok(())
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+/// Vendor-specific runtime features that are not part of the closed `RuntimeFeatureFlags` set, identified by name.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ExtendedFeatures(BTreeSet<String>);
+
+impl Display for ExtendedFeatures {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let names = self.names().collect::<Vec<_>>();
+        write!(f, "ExtendedFeatures({})", names.join(" | "))
+    }
+}
+
+impl ExtendedFeatures {
+    #[must_use]
+    pub fn empty() -> Self {
+        Self(BTreeSet::new())
+    }
+
+    /// Creates a set that only contains the feature with the provided name.
+    #[must_use]
+    pub fn named(name: &str) -> Self {
+        Self(BTreeSet::from([name.to_string()]))
+    }
+
+    #[must_use]
+    pub fn contains(&self, other: &Self) -> bool {
+        other.0.is_subset(&self.0)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Gets the names of the features in the set, in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self(self.0.union(&other.0).cloned().collect())
+    }
+}
+
+#[derive(Clone, Debug)]
 pub enum ValueKind {
     /// The first runtime kind corresponds to the content of the array while the second corresponds to the size.
     Array(RuntimeKind, RuntimeKind),
+    /// For arrays whose elements are arrays themselves, the value kind corresponds to the content of the inner arrays
+    /// while the runtime kind corresponds to the size of the outer array.
+    NestedArray(Box<ValueKind>, RuntimeKind),
     /// Runtime kind correspondig to a single element.
     Element(RuntimeKind),
+    /// Value kinds corresponding to each element of a tuple.
+    Tuple(Vec<ValueKind>),
 }
 
 impl Display for ValueKind {
@@ -583,7 +1305,19 @@ impl Display for ValueKind {
                 f,
                 "Array(Content: {content_runtime_value}, Size: {size_runtime_value})"
             )?,
+            Self::NestedArray(content_value_kind, size_runtime_value) => write!(
+                f,
+                "Array(Content: {content_value_kind}, Size: {size_runtime_value})"
+            )?,
             Self::Element(runtime_value) => write!(f, "Element({runtime_value})")?,
+            Self::Tuple(value_kinds) => {
+                let value_kinds = value_kinds
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "Tuple({value_kinds})")?;
+            }
         };
         Ok(())
     }
@@ -597,7 +1331,20 @@ impl ValueKind {
         } else {
             match ty {
                 // For a dynamic array, both contents and size are dynamic.
-                Ty::Array(_) => ValueKind::Array(RuntimeKind::Dynamic, RuntimeKind::Dynamic),
+                Ty::Array(content_type) => match content_type.as_ref() {
+                    Ty::Array(_) => ValueKind::NestedArray(
+                        Box::new(ValueKind::new_dynamic_from_type(content_type)),
+                        RuntimeKind::Dynamic,
+                    ),
+                    _ => ValueKind::Array(RuntimeKind::Dynamic, RuntimeKind::Dynamic),
+                },
+                // For a dynamic tuple, every element is dynamic.
+                Ty::Tuple(element_types) => ValueKind::Tuple(
+                    element_types
+                        .iter()
+                        .map(ValueKind::new_dynamic_from_type)
+                        .collect(),
+                ),
                 // For every other dynamic type, we use the element variant with a dynamic runtime value.
                 _ => ValueKind::Element(RuntimeKind::Dynamic),
             }
@@ -607,65 +1354,152 @@ impl ValueKind {
     pub(crate) fn new_static_from_type(ty: &Ty) -> Self {
         match ty {
             // For a static array, both contents and size are static.
-            Ty::Array(_) => ValueKind::Array(RuntimeKind::Static, RuntimeKind::Static),
+            Ty::Array(content_type) => match content_type.as_ref() {
+                Ty::Array(_) => ValueKind::NestedArray(
+                    Box::new(ValueKind::new_static_from_type(content_type)),
+                    RuntimeKind::Static,
+                ),
+                _ => ValueKind::Array(RuntimeKind::Static, RuntimeKind::Static),
+            },
+            // For a static tuple, every element is static.
+            Ty::Tuple(element_types) if !element_types.is_empty() => ValueKind::Tuple(
+                element_types
+                    .iter()
+                    .map(ValueKind::new_static_from_type)
+                    .collect(),
+            ),
             // For every other static type, we use the element variant with a static runtime value.
             _ => ValueKind::Element(RuntimeKind::Static),
         }
     }
 
     pub(crate) fn aggregate(self, value: ValueKind) -> Self {
-        match self {
-            Self::Array(self_content_runtime_value, self_size_runtime_value) => {
-                let Self::Array(other_content_runtime_value, other_size_runtime_value) = value
-                else {
-                    panic!("only value kinds of the same variant can be aggregated");
-                };
-
-                Self::Array(
-                    self_content_runtime_value.aggregate(other_content_runtime_value),
-                    self_size_runtime_value.aggregate(other_size_runtime_value),
-                )
+        match (self, value) {
+            (
+                Self::Array(self_content_runtime_value, self_size_runtime_value),
+                Self::Array(other_content_runtime_value, other_size_runtime_value),
+            ) => Self::Array(
+                self_content_runtime_value.aggregate(other_content_runtime_value),
+                self_size_runtime_value.aggregate(other_size_runtime_value),
+            ),
+            (
+                Self::NestedArray(self_content_value_kind, self_size_runtime_value),
+                Self::NestedArray(other_content_value_kind, other_size_runtime_value),
+            ) => Self::NestedArray(
+                Box::new(self_content_value_kind.aggregate(*other_content_value_kind)),
+                self_size_runtime_value.aggregate(other_size_runtime_value),
+            ),
+            // A flat array value kind can be aggregated to a nested one by projecting it onto the nested variant.
+            (nested @ Self::NestedArray(..), array @ Self::Array(..))
+            | (array @ Self::Array(..), nested @ Self::NestedArray(..)) => {
+                let mut projected = nested.clone();
+                array.project_onto_variant(&mut projected);
+                nested.aggregate(projected)
             }
-            Self::Element(self_runtime_value) => {
-                let Self::Element(other_runtime_value) = value else {
-                    panic!("only value kinds of the same variant can be aggregated");
-                };
+            (Self::Element(self_runtime_value), Self::Element(other_runtime_value)) => {
                 Self::Element(self_runtime_value.aggregate(other_runtime_value))
             }
+            (Self::Tuple(self_value_kinds), Self::Tuple(other_value_kinds))
+                if self_value_kinds.len() == other_value_kinds.len() =>
+            {
+                Self::Tuple(
+                    self_value_kinds
+                        .into_iter()
+                        .zip(other_value_kinds)
+                        .map(|(self_value_kind, other_value_kind)| {
+                            self_value_kind.aggregate(other_value_kind)
+                        })
+                        .collect(),
+                )
+            }
+            // An element value kind can be aggregated to a tuple by projecting it onto each of the tuple elements.
+            (Self::Tuple(value_kinds), element @ Self::Element(_))
+            | (element @ Self::Element(_), Self::Tuple(value_kinds)) => {
+                let mut projected_value_kinds = value_kinds.clone();
+                for projected_value_kind in &mut projected_value_kinds {
+                    element.project_onto_variant(projected_value_kind);
+                }
+                Self::Tuple(value_kinds).aggregate(Self::Tuple(projected_value_kinds))
+            }
+            _ => panic!("only value kinds of the same variant can be aggregated"),
         }
     }
 
-    pub(crate) fn is_dynamic(self) -> bool {
+    pub(crate) fn is_dynamic(&self) -> bool {
         match self {
             Self::Array(content_runtime_kind, size_runtime_kind) => {
                 matches!(content_runtime_kind, RuntimeKind::Dynamic)
                     || matches!(size_runtime_kind, RuntimeKind::Dynamic)
             }
+            Self::NestedArray(content_value_kind, size_runtime_kind) => {
+                content_value_kind.is_dynamic() || matches!(size_runtime_kind, RuntimeKind::Dynamic)
+            }
             Self::Element(runtime_kind) => matches!(runtime_kind, RuntimeKind::Dynamic),
+            Self::Tuple(value_kinds) => value_kinds.iter().any(ValueKind::is_dynamic),
         }
     }
 
-    pub(crate) fn project_onto_variant(self, variant: &mut ValueKind) {
+    pub(crate) fn project_onto_variant(&self, variant: &mut ValueKind) {
         match variant {
             ValueKind::Array(content_runtime_kind, size_runtime_kind) => match self {
                 // We should resolve to an array value kind variant.
                 ValueKind::Array(self_content_runtime_kind, self_size_runtime_kind) => {
-                    *content_runtime_kind = self_content_runtime_kind;
-                    *size_runtime_kind = self_size_runtime_kind;
+                    *content_runtime_kind = *self_content_runtime_kind;
+                    *size_runtime_kind = *self_size_runtime_kind;
+                }
+                ValueKind::NestedArray(self_content_value_kind, self_size_runtime_kind) => {
+                    *content_runtime_kind =
+                        RuntimeKind::from_is_dynamic(self_content_value_kind.is_dynamic());
+                    *size_runtime_kind = *self_size_runtime_kind;
                 }
                 ValueKind::Element(self_runtime_kind) => {
-                    *content_runtime_kind = self_runtime_kind;
-                    *size_runtime_kind = self_runtime_kind;
+                    *content_runtime_kind = *self_runtime_kind;
+                    *size_runtime_kind = *self_runtime_kind;
+                }
+                ValueKind::Tuple(_) => {
+                    let runtime_kind = RuntimeKind::from_is_dynamic(self.is_dynamic());
+                    *content_runtime_kind = runtime_kind;
+                    *size_runtime_kind = runtime_kind;
+                }
+            },
+            ValueKind::NestedArray(content_value_kind, size_runtime_kind) => match self {
+                // We should resolve to a nested array value kind variant, recursing into the inner arrays.
+                ValueKind::NestedArray(self_content_value_kind, self_size_runtime_kind) => {
+                    self_content_value_kind.project_onto_variant(content_value_kind);
+                    *size_runtime_kind = *self_size_runtime_kind;
+                }
+                ValueKind::Array(self_content_runtime_kind, self_size_runtime_kind) => {
+                    ValueKind::Element(*self_content_runtime_kind)
+                        .project_onto_variant(content_value_kind);
+                    *size_runtime_kind = *self_size_runtime_kind;
+                }
+                ValueKind::Element(_) | ValueKind::Tuple(_) => {
+                    let runtime_kind = RuntimeKind::from_is_dynamic(self.is_dynamic());
+                    ValueKind::Element(runtime_kind).project_onto_variant(content_value_kind);
+                    *size_runtime_kind = runtime_kind;
                 }
             },
             ValueKind::Element(runtime_kind) => {
                 // We should resolve to an element value kind variant.
-                *runtime_kind = if self.is_dynamic() {
-                    RuntimeKind::Dynamic
-                } else {
-                    RuntimeKind::Static
-                };
+                *runtime_kind = RuntimeKind::from_is_dynamic(self.is_dynamic());
             }
+            ValueKind::Tuple(value_kinds) => match self {
+                // We should resolve to a tuple value kind variant, element by element when possible.
+                ValueKind::Tuple(self_value_kinds)
+                    if self_value_kinds.len() == value_kinds.len() =>
+                {
+                    for (self_value_kind, value_kind) in self_value_kinds.iter().zip(value_kinds) {
+                        self_value_kind.project_onto_variant(value_kind);
+                    }
+                }
+                _ => {
+                    let element_value_kind =
+                        ValueKind::Element(RuntimeKind::from_is_dynamic(self.is_dynamic()));
+                    for value_kind in value_kinds {
+                        element_value_kind.project_onto_variant(value_kind);
+                    }
+                }
+            },
         };
     }
 }
@@ -697,6 +1531,14 @@ impl RuntimeKind {
             Self::Dynamic => Self::Dynamic,
         }
     }
+
+    fn from_is_dynamic(is_dynamic: bool) -> Self {
+        if is_dynamic {
+            Self::Dynamic
+        } else {
+            Self::Static
+        }
+    }
 }
 
 bitflags! {
@@ -750,6 +1592,15 @@ bitflags! {
         const LoopWithDynamicCondition = 1 << 21;
         /// Use of a closure.
         const UseOfClosure = 1 << 22;
+        /// Qubit release that happens within a dynamic scope.
+        const DynamicQubitRelease = 1 << 23;
+        /// A loop that possibly never terminates.
+        const PossiblyNonTerminating = 1 << 24;
+        /// Use of a dynamic value as an operand of an arithmetic, bitwise or shift operation that computes an integer
+        /// value, whatever the source of its dynamism (e.g. a measurement result or a dynamically sized array).
+        const DynamicOperandInIntArithmetic = 1 << 25;
+        /// Use of a dynamic value as an operand of an arithmetic operation that computes a floating-point value.
+        const DynamicOperandInDoubleArithmetic = 1 << 26;
     }
 }
 
@@ -843,6 +1694,18 @@ impl RuntimeFeatureFlags {
         if self.contains(RuntimeFeatureFlags::UseOfClosure) {
             runtume_capabilities |= RuntimeCapabilityFlags::HigherLevelConstructs;
         }
+        if self.contains(RuntimeFeatureFlags::DynamicQubitRelease) {
+            runtume_capabilities |= RuntimeCapabilityFlags::HigherLevelConstructs;
+        }
+        if self.contains(RuntimeFeatureFlags::PossiblyNonTerminating) {
+            runtume_capabilities |= RuntimeCapabilityFlags::BackwardsBranching;
+        }
+        if self.contains(RuntimeFeatureFlags::DynamicOperandInIntArithmetic) {
+            runtume_capabilities |= RuntimeCapabilityFlags::IntegerComputations;
+        }
+        if self.contains(RuntimeFeatureFlags::DynamicOperandInDoubleArithmetic) {
+            runtume_capabilities |= RuntimeCapabilityFlags::FloatingPointComputations;
+        }
         runtume_capabilities
     }
 }
@@ -2,9 +2,10 @@
 // Licensed under the MIT License.
 
 use crate::{
-    common::LocalSpecId, scaffolding::InternalPackageStoreComputeProperties,
-    ApplicationGeneratorSet, ArrayParamApplication, ComputeKind, PackageId, ParamApplication,
-    QuantumProperties, RuntimeFeatureFlags, RuntimeKind, ValueKind,
+    common::{get_spec_decl, LocalSpecId},
+    scaffolding::InternalPackageStoreComputeProperties,
+    ApplicationGeneratorSet, ArrayParamApplication, ComputeKind, ExtendedFeatures, PackageId,
+    ParamApplication, QuantumProperties, RuntimeFeatureFlags, RuntimeKind, ValueKind,
 };
 use qsc_fir::{
     fir::{
@@ -45,19 +46,25 @@ impl<'a> Overrider<'a> {
                     dynamic_param_applications: vec![ParamApplication::Array(
                         ArrayParamApplication {
                             static_content_dynamic_size: ComputeKind::Quantum(QuantumProperties {
-                                runtime_features: RuntimeFeatureFlags::UseOfDynamicallySizedArray,
+                                runtime_features: RuntimeFeatureFlags::UseOfDynamicInt
+                                    | RuntimeFeatureFlags::UseOfDynamicallySizedArray,
+                                extended_features: ExtendedFeatures::empty(),
                                 value_kind: ValueKind::Element(RuntimeKind::Dynamic),
                             }),
                             dynamic_content_static_size: ComputeKind::Quantum(QuantumProperties {
                                 runtime_features: RuntimeFeatureFlags::empty(),
+                                extended_features: ExtendedFeatures::empty(),
                                 value_kind: ValueKind::Element(RuntimeKind::Static),
                             }),
                             dynamic_content_dynamic_size: ComputeKind::Quantum(QuantumProperties {
-                                runtime_features: RuntimeFeatureFlags::UseOfDynamicallySizedArray,
+                                runtime_features: RuntimeFeatureFlags::UseOfDynamicInt
+                                    | RuntimeFeatureFlags::UseOfDynamicallySizedArray,
+                                extended_features: ExtendedFeatures::empty(),
                                 value_kind: ValueKind::Element(RuntimeKind::Dynamic),
                             }),
                         },
                     )],
+                    from_cycle_assumption: false,
                 },
             }],
         )];
@@ -125,21 +132,7 @@ impl<'a> Overrider<'a> {
         // If the specialization is not intrinsic, we need to visit the implementation to populate the properties of its
         // elements.
         if let CallableImpl::Spec(spec_impl) = &callable_decl.implementation {
-            let spec_decl = match spec_id.functor_set_value {
-                FunctorSetValue::Empty => &spec_impl.body,
-                FunctorSetValue::Adj => spec_impl
-                    .adj
-                    .as_ref()
-                    .expect("adj specialization should exist"),
-                FunctorSetValue::Ctl => spec_impl
-                    .ctl
-                    .as_ref()
-                    .expect("ctl specialization should exist"),
-                FunctorSetValue::CtlAdj => spec_impl
-                    .ctl_adj
-                    .as_ref()
-                    .expect("ctl_adj specializatiob should exist"),
-            };
+            let spec_decl = get_spec_decl(spec_impl, spec_id.functor_set_value);
             self.visit_spec_decl(spec_decl);
         }
 
@@ -261,7 +254,7 @@ fn adapt_application_generator_set_to_type(
     application_generator_set: &ApplicationGeneratorSet,
     ty: &Ty,
 ) -> ApplicationGeneratorSet {
-    let inherent = adapt_compute_kind_to_type(application_generator_set.inherent, ty);
+    let inherent = adapt_compute_kind_to_type(&application_generator_set.inherent, ty);
     let mut dynamic_param_applications = Vec::new();
     for param_application in &application_generator_set.dynamic_param_applications {
         let param_application = adapt_param_application_to_type(param_application, ty);
@@ -270,10 +263,11 @@ fn adapt_application_generator_set_to_type(
     ApplicationGeneratorSet {
         inherent,
         dynamic_param_applications,
+        from_cycle_assumption: application_generator_set.from_cycle_assumption,
     }
 }
 
-fn adapt_compute_kind_to_type(compute_kind: ComputeKind, ty: &Ty) -> ComputeKind {
+fn adapt_compute_kind_to_type(compute_kind: &ComputeKind, ty: &Ty) -> ComputeKind {
     match compute_kind {
         ComputeKind::Classical => ComputeKind::Classical,
         ComputeKind::Quantum(quantum_properties) => {
@@ -284,6 +278,7 @@ fn adapt_compute_kind_to_type(compute_kind: ComputeKind, ty: &Ty) -> ComputeKind
                 .project_onto_variant(&mut value_kind);
             ComputeKind::Quantum(QuantumProperties {
                 runtime_features,
+                extended_features: quantum_properties.extended_features.clone(),
                 value_kind,
             })
         }
@@ -296,12 +291,16 @@ fn adapt_param_application_to_type(
 ) -> ParamApplication {
     match param_application {
         ParamApplication::Array(array_param_application) => {
-            let static_content_dynamic_size =
-                adapt_compute_kind_to_type(array_param_application.static_content_dynamic_size, ty);
-            let dynamic_content_static_size =
-                adapt_compute_kind_to_type(array_param_application.dynamic_content_static_size, ty);
+            let static_content_dynamic_size = adapt_compute_kind_to_type(
+                &array_param_application.static_content_dynamic_size,
+                ty,
+            );
+            let dynamic_content_static_size = adapt_compute_kind_to_type(
+                &array_param_application.dynamic_content_static_size,
+                ty,
+            );
             let dynamic_content_dynamic_size = adapt_compute_kind_to_type(
-                array_param_application.dynamic_content_dynamic_size,
+                &array_param_application.dynamic_content_dynamic_size,
                 ty,
             );
             ParamApplication::Array(ArrayParamApplication {
@@ -311,7 +310,7 @@ fn adapt_param_application_to_type(
             })
         }
         ParamApplication::Element(compute_kind) => {
-            let compute_kind = adapt_compute_kind_to_type(*compute_kind, ty);
+            let compute_kind = adapt_compute_kind_to_type(compute_kind, ty);
             ParamApplication::Element(compute_kind)
         }
     }
@@ -16,7 +16,7 @@ use qsc_fir::{
 };
 
 /// Scaffolding used to build the package store compute properties.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct InternalPackageStoreComputeProperties(
     IndexMap<PackageId, InternalPackageComputeProperties>,
 );
@@ -35,6 +35,7 @@ impl From<PackageStoreComputeProperties> for InternalPackageStoreComputeProperti
                 blocks: package_compute_properties.blocks,
                 stmts: package_compute_properties.stmts,
                 exprs: package_compute_properties.exprs,
+                dynamic_scope_depths: package_compute_properties.dynamic_scope_depths,
             };
             scaffolding.insert(package_id, package_compute_properties);
         }
@@ -59,6 +60,7 @@ impl From<InternalPackageStoreComputeProperties> for PackageStoreComputeProperti
                 blocks: package_scaffolding.blocks,
                 stmts: package_scaffolding.stmts,
                 exprs: package_scaffolding.exprs,
+                dynamic_scope_depths: package_scaffolding.dynamic_scope_depths,
             };
             package_store_compute_properties.insert(package_id, package_compute_properties);
         }
@@ -181,6 +183,14 @@ impl InternalPackageStoreComputeProperties {
     pub fn insert_stmt(&mut self, id: StoreStmtId, value: ApplicationGeneratorSet) {
         self.get_mut(id.package).stmts.insert(id.stmt, value);
     }
+
+    pub fn update_dynamic_scope_depth(&mut self, id: StoreItemId, depth: usize) {
+        let dynamic_scope_depths = &mut self.get_mut(id.package).dynamic_scope_depths;
+        let max_depth = dynamic_scope_depths
+            .get(id.item)
+            .map_or(depth, |current_depth| depth.max(*current_depth));
+        dynamic_scope_depths.insert(id.item, max_depth);
+    }
 }
 
 /// Scaffolding used to build the compute properties of a package.
@@ -194,6 +204,8 @@ pub struct InternalPackageComputeProperties {
     pub stmts: IndexMap<StmtId, ApplicationGeneratorSet>,
     /// The application generator sets of the package expressions.
     pub exprs: IndexMap<ExprId, ApplicationGeneratorSet>,
+    /// The maximum depth of nested dynamic scopes across the specializations of each callable in the package.
+    pub dynamic_scope_depths: IndexMap<LocalItemId, usize>,
 }
 
 /// Scaffolding used to build the compute properties of an item.
@@ -210,7 +222,7 @@ impl From<ItemComputeProperties> for InternalItemComputeProperties {
             ItemComputeProperties::NonCallable => InternalItemComputeProperties::NonCallable,
             ItemComputeProperties::Callable(callable_compute_properties) => {
                 InternalItemComputeProperties::Specializations(
-                    SpecializationsComputeProperties::from(callable_compute_properties),
+                    SpecializationsComputeProperties::from(*callable_compute_properties),
                 )
             }
         }
@@ -222,7 +234,9 @@ impl From<InternalItemComputeProperties> for ItemComputeProperties {
         match value {
             InternalItemComputeProperties::NonCallable => ItemComputeProperties::NonCallable,
             InternalItemComputeProperties::Specializations(specializations) => {
-                ItemComputeProperties::Callable(CallableComputeProperties::from(specializations))
+                ItemComputeProperties::Callable(Box::new(CallableComputeProperties::from(
+                    specializations,
+                )))
             }
         }
     }
@@ -180,6 +180,34 @@ fn check_rca_for_array_repeat_with_dynamic_double_value_and_dynamic_size() {
     );
 }
 
+#[test]
+fn check_rca_for_array_repeat_with_classical_value_and_dynamic_size_from_nested_function_calls() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        function Twice(i : Int) : Int {
+            2 * i
+        }
+        function SizeFor(r : Result) : Int {
+            Twice(r == Zero ? 5 | 10)
+        }
+        use q = Qubit();
+        [Zero, size = SizeFor(M(q))]"#,
+    );
+    let package_store_compute_properties = compilation_context.get_compute_properties();
+    check_last_statement_compute_properties(
+        package_store_compute_properties,
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicInt | UseOfDynamicallySizedArray | DynamicOperandInIntArithmetic)
+                    value_kind: Array(Content: Static, Size: Dynamic)
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+}
+
 #[test]
 fn check_rca_for_mutable_array_statically_appended() {
     let mut compilation_context = CompilationContext::default();
@@ -195,12 +223,14 @@ fn check_rca_for_mutable_array_statically_appended() {
     let package_store_compute_properties = compilation_context.get_compute_properties();
     check_last_statement_compute_properties(
         package_store_compute_properties,
-        &expect![[r#"
+        &expect![
+            r#"
             ApplicationsGeneratorSet:
                 inherent: Quantum: QuantumProperties:
                     runtime_features: RuntimeFeatureFlags(0x0)
                     value_kind: Array(Content: Dynamic, Size: Static)
-                dynamic_param_applications: <empty>"#]],
+                dynamic_param_applications: <empty>"#
+        ],
     );
 }
 
@@ -219,11 +249,13 @@ fn check_rca_for_mutable_array_dynamically_appended() {
     let package_store_compute_properties = compilation_context.get_compute_properties();
     check_last_statement_compute_properties(
         package_store_compute_properties,
-        &expect![[r#"
+        &expect![
+            r#"
             ApplicationsGeneratorSet:
                 inherent: Quantum: QuantumProperties:
                     runtime_features: RuntimeFeatureFlags(UseOfDynamicInt | UseOfDynamicallySizedArray)
                     value_kind: Array(Content: Dynamic, Size: Dynamic)
-                dynamic_param_applications: <empty>"#]],
+                dynamic_param_applications: <empty>"#
+        ],
     );
 }
@@ -6,6 +6,7 @@
 pub mod test_utils;
 
 use expect_test::expect;
+use qsc_rca::Analyzer;
 use test_utils::{check_last_statement_compute_properties, CompilationContext};
 
 #[test]
@@ -132,3 +133,58 @@ fn check_rca_for_dynamic_double_assign_to_local() {
         ],
     );
 }
+
+#[test]
+fn check_rca_for_dynamic_int_accumulated_in_loop() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        use register = Qubit[3];
+        mutable total = 0;
+        for q in register {
+            set total += M(q) == One ? 1 | 0;
+        }
+        total"#,
+    );
+    let package_store_compute_properties = compilation_context.get_compute_properties();
+    check_last_statement_compute_properties(
+        package_store_compute_properties,
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicInt | DynamicOperandInIntArithmetic)
+                    value_kind: Element(Dynamic)
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_new_top_level_assign_to_dynamic_local_from_previous_statement() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        use q = Qubit();
+        mutable i = M(q) == One ? 1 | 0;"#,
+    );
+    let package_id = compilation_context.lower_fragments("set i += 1;");
+    let stmt_id = compilation_context
+        .fir_store
+        .get(package_id)
+        .stmts
+        .iter()
+        .map(|(stmt_id, _)| stmt_id)
+        .max()
+        .expect("at least one statement should exist");
+    let mut analyzer = Analyzer::init_with_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties().clone(),
+    );
+    let compute_kind = analyzer.analyze_new_top_level_stmt(package_id, stmt_id);
+    expect![[r#"
+        Quantum: QuantumProperties:
+            runtime_features: RuntimeFeatureFlags(DynamicOperandInIntArithmetic)
+            value_kind: Element(Static)"#]]
+    .assert_eq(&compute_kind.to_string());
+}
@@ -189,3 +189,92 @@ fn check_rca_for_mutable_dynamic_double_binding() {
         ],
     );
 }
+
+#[test]
+fn check_rca_for_nested_tuple_destructuring_binding_with_mixed_dynamism() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        use q = Qubit();
+        let ((a, b), (c, d)) = ((M(q), 1), (2.0, M(q) == One));"#,
+    );
+    compilation_context.update("a");
+    check_last_statement_compute_properties(
+        compilation_context.get_compute_properties(),
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(0x0)
+                    value_kind: Element(Dynamic)
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+    compilation_context.update("b");
+    check_last_statement_compute_properties(
+        compilation_context.get_compute_properties(),
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Classical
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+    compilation_context.update("c");
+    check_last_statement_compute_properties(
+        compilation_context.get_compute_properties(),
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Classical
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+    compilation_context.update("d");
+    check_last_statement_compute_properties(
+        compilation_context.get_compute_properties(),
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool)
+                    value_kind: Element(Dynamic)
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_nested_tuple_destructuring_of_tuple_variable_with_mixed_dynamism() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        use q = Qubit();
+        let t = ((M(q), 1), (2.0, false));
+        let ((a, b), (c, d)) = t;"#,
+    );
+    compilation_context.update("a");
+    check_last_statement_compute_properties(
+        compilation_context.get_compute_properties(),
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(0x0)
+                    value_kind: Element(Dynamic)
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+    compilation_context.update("d");
+    check_last_statement_compute_properties(
+        compilation_context.get_compute_properties(),
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(0x0)
+                    value_kind: Element(Static)
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+}
@@ -6,7 +6,11 @@
 pub mod test_utils;
 
 use expect_test::expect;
-use test_utils::{check_last_statement_compute_properties, CompilationContext};
+use qsc_frontend::compile::RuntimeCapabilityFlags;
+use qsc_rca::RuntimeFeatureFlags;
+use test_utils::{
+    check_callable_compute_properties, check_last_statement_compute_properties, CompilationContext,
+};
 
 #[test]
 fn check_rca_for_bin_op_with_classical_lhs_and_classical_rhs() {
@@ -127,9 +131,137 @@ fn check_rca_for_nested_bin_ops_with_a_dynamic_operand() {
             r#"
             ApplicationsGeneratorSet:
                 inherent: Quantum: QuantumProperties:
-                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicInt)
+                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicInt | DynamicOperandInIntArithmetic)
                     value_kind: Element(Dynamic)
                 dynamic_param_applications: <empty>"#
         ],
     );
 }
+
+#[test]
+fn check_rca_for_bitwise_and_with_dynamic_int_operand() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        use q = Qubit();
+        (M(q) == One ? 5 | 3) &&& 1"#,
+    );
+    let package_store_compute_properties = compilation_context.get_compute_properties();
+    check_last_statement_compute_properties(
+        package_store_compute_properties,
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicInt | DynamicOperandInIntArithmetic)
+                    value_kind: Element(Dynamic)
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_nested_bitwise_ops_with_a_dynamic_int_operand() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        use q = Qubit();
+        let i = M(q) == Zero ? 0 | 1;
+        (i ||| 2) ^^^ 4"#,
+    );
+    let package_store_compute_properties = compilation_context.get_compute_properties();
+    check_last_statement_compute_properties(
+        package_store_compute_properties,
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicInt | DynamicOperandInIntArithmetic)
+                    value_kind: Element(Dynamic)
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_sum_of_dynamic_ints_uses_arithmetic_without_branching() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        use (q0, q1) = (Qubit(), Qubit());
+        let a = M(q0) == One ? 1 | 0;
+        let b = M(q1) == One ? 1 | 0;
+        a + b"#,
+    );
+    let package_store_compute_properties = compilation_context.get_compute_properties();
+    check_last_statement_compute_properties(
+        package_store_compute_properties,
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicInt | DynamicOperandInIntArithmetic)
+                    value_kind: Element(Dynamic)
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_sum_of_dynamic_doubles_uses_floating_point_arithmetic() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        use (q0, q1) = (Qubit(), Qubit());
+        let a = M(q0) == One ? 1.0 | 0.0;
+        let b = M(q1) == One ? 1.0 | 0.0;
+        a + b"#,
+    );
+    let package_store_compute_properties = compilation_context.get_compute_properties();
+    check_last_statement_compute_properties(
+        package_store_compute_properties,
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicDouble | DynamicOperandInDoubleArithmetic)
+                    value_kind: Element(Dynamic)
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+    assert_eq!(
+        RuntimeFeatureFlags::DynamicOperandInDoubleArithmetic.runtime_capabilities(),
+        RuntimeCapabilityFlags::FloatingPointComputations
+    );
+}
+
+#[test]
+fn check_rca_for_branching_on_dynamic_result_uses_branching_without_arithmetic() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo() : Unit {
+            use q = Qubit();
+            if M(q) == One {
+                X(q);
+            }
+        }"#,
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | ForwardBranchingOnDynamicValue)
+                        value_kind: Element(Static)
+                    dynamic_param_applications: <empty>
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
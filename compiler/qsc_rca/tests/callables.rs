@@ -7,8 +7,14 @@ pub mod test_utils;
 
 use expect_test::expect;
 use qsc::RuntimeCapabilityFlags;
+use qsc_fir::fir::ItemKind;
+use qsc_rca::{
+    Analyzer, ComputeKind, ComputePropertiesLookup, InputParamIndex, ItemComputeProperties,
+    RuntimeFeatureFlags, RuntimeKind, ValueKind,
+};
 use test_utils::{
     check_callable_compute_properties, check_last_statement_compute_properties, CompilationContext,
+    PackageStoreSearch,
 };
 
 #[test]
@@ -142,7 +148,7 @@ fn check_rca_for_operation_with_one_classical_return_and_one_dynamic_return() {
             Callable: CallableComputeProperties:
                 body: ApplicationsGeneratorSet:
                     inherent: Quantum: QuantumProperties:
-                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | ForwardBranchingOnDynamicValue | ReturnWithinDynamicScope)
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | ForwardBranchingOnDynamicValue | ReturnWithinDynamicScope | DynamicQubitRelease)
                         value_kind: Element(Dynamic)
                     dynamic_param_applications: <empty>
                 adj: <none>
@@ -1587,3 +1593,815 @@ fn check_rca_for_base_z() {
         ],
     );
 }
+
+#[test]
+fn check_rca_progress_is_reported_for_each_item() {
+    let compilation_context = CompilationContext::default();
+    let total_items = compilation_context
+        .fir_store
+        .iter()
+        .map(|(_, package)| package.items.iter().count())
+        .sum::<usize>();
+    let mut callbacks = 0;
+    let _ =
+        Analyzer::init(&compilation_context.fir_store).analyze_all_with_progress(|done, total| {
+            callbacks += 1;
+            assert_eq!(done, callbacks);
+            assert_eq!(total, total_items);
+        });
+    assert_eq!(callbacks, total_items);
+}
+
+#[test]
+fn check_rca_application_table_for_function_with_two_parameters() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        function Foo(a : Int, b : Int) : Int {
+            a + b
+        }"#,
+    );
+    let callable_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let ItemComputeProperties::Callable(callable_compute_properties) = compilation_context
+        .get_compute_properties()
+        .get_item(callable_id)
+    else {
+        panic!("item should be a callable");
+    };
+    expect![[r#"
+        [0]              | [1]              | compute_kind
+        Element(Static)  | Element(Static)  | Classical
+        Element(Static)  | Element(Dynamic) | Quantum: RuntimeFeatureFlags(UseOfDynamicInt | DynamicOperandInIntArithmetic), Element(Dynamic)
+        Element(Dynamic) | Element(Static)  | Quantum: RuntimeFeatureFlags(UseOfDynamicInt | DynamicOperandInIntArithmetic), Element(Dynamic)
+        Element(Dynamic) | Element(Dynamic) | Quantum: RuntimeFeatureFlags(UseOfDynamicInt | DynamicOperandInIntArithmetic), Element(Dynamic)
+    "#]]
+    .assert_eq(&callable_compute_properties.body.to_table_string());
+}
+
+#[test]
+fn check_rca_for_operation_without_functors() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Unit {
+            H(q);
+        }"#,
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(0x0)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicQubit)
+                            value_kind: Element(Static)
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_operation_with_adj_only() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Unit is Adj {
+            H(q);
+        }"#,
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(0x0)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicQubit)
+                            value_kind: Element(Static)
+                adj: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(0x0)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicQubit)
+                            value_kind: Element(Static)
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_operation_with_ctl_only() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Unit is Ctl {
+            H(q);
+        }"#,
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(0x0)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicQubit)
+                            value_kind: Element(Static)
+                adj: <none>
+                ctl: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | ForwardBranchingOnDynamicValue)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicQubit | ForwardBranchingOnDynamicValue)
+                            value_kind: Element(Static)
+                ctl-adj: <none>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_operation_with_adj_and_ctl() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Unit is Adj + Ctl {
+            H(q);
+        }"#,
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(0x0)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicQubit)
+                            value_kind: Element(Static)
+                adj: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(0x0)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicQubit)
+                            value_kind: Element(Static)
+                ctl: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | ForwardBranchingOnDynamicValue)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicQubit | ForwardBranchingOnDynamicValue)
+                            value_kind: Element(Static)
+                ctl-adj: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | ForwardBranchingOnDynamicValue)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicQubit | ForwardBranchingOnDynamicValue)
+                            value_kind: Element(Static)"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_per_specialization_features_for_operation_with_adj_and_ctl() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Unit is Adj + Ctl {
+            H(q);
+        }"#,
+    );
+    let callable_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let ItemComputeProperties::Callable(callable_compute_properties) = compilation_context
+        .get_compute_properties()
+        .get_item(callable_id)
+    else {
+        panic!("item should be a callable");
+    };
+    expect![[r#"
+        {
+            Empty: RuntimeFeatureFlags(
+                0x0,
+            ),
+            Adj: RuntimeFeatureFlags(
+                0x0,
+            ),
+            Ctl: RuntimeFeatureFlags(
+                UseOfDynamicBool | ForwardBranchingOnDynamicValue,
+            ),
+            CtlAdj: RuntimeFeatureFlags(
+                UseOfDynamicBool | ForwardBranchingOnDynamicValue,
+            ),
+        }"#]]
+    .assert_eq(&format!(
+        "{:#?}",
+        callable_compute_properties.per_specialization_features()
+    ));
+}
+
+#[test]
+fn check_rca_same_profile_for_equivalent_and_differing_operations() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation FlipIfOne(q : Qubit) : Unit {
+            if M(q) == One {
+                X(q);
+            }
+        }
+        operation PhaseIfOne(q : Qubit) : Unit {
+            if M(q) == One {
+                Z(q);
+            }
+        }
+        operation Prepare(q : Qubit) : Unit {
+            H(q);
+        }"#,
+    );
+    let find_callable_id = |name| {
+        compilation_context
+            .fir_store
+            .find_callable_id_by_name(name)
+            .expect("callable should exist")
+    };
+    let flip_if_one_id = find_callable_id("FlipIfOne");
+    let phase_if_one_id = find_callable_id("PhaseIfOne");
+    let prepare_id = find_callable_id("Prepare");
+    let compute_properties = compilation_context.get_compute_properties();
+    assert!(compute_properties.same_profile(flip_if_one_id, phase_if_one_id));
+    assert!(!compute_properties.same_profile(flip_if_one_id, prepare_id));
+}
+
+#[test]
+fn check_rca_for_closure_operation_with_captured_qubit_applied_in_dynamic_branch() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation RotateByMeasurement(q : Qubit) : Unit {
+            let angle = M(q) == One ? 1.0 | 2.0;
+            Rx(angle, q);
+        }
+        operation Foo() : Unit {
+            use (condition, target) = (Qubit(), Qubit());
+            let rotate = () => RotateByMeasurement(target);
+            if M(condition) == One {
+                rotate();
+            }
+        }"#,
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicDouble | CallToDynamicCallee | ForwardBranchingOnDynamicValue | UseOfClosure)
+                        value_kind: Element(Static)
+                    dynamic_param_applications: <empty>
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_operation_analyzed_with_only_third_param_dynamic() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(n : Int, b : Bool, theta : Double, q : Qubit) : Unit {
+            if b {
+                X(q);
+            }
+            for _ in 1..n {
+                Rx(theta, q);
+            }
+        }"#,
+    );
+    let foo_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let mut analyzer = Analyzer::init_with_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties().clone(),
+    );
+    let compute_kind = analyzer
+        .analyze_spec_with_dynamic_params(foo_id, &[InputParamIndex::from(2)])
+        .expect("item should be a callable");
+    expect![[r#"
+        Quantum: QuantumProperties:
+            runtime_features: RuntimeFeatureFlags(UseOfDynamicDouble)
+            value_kind: Element(Static)"#]]
+    .assert_eq(&compute_kind.to_string());
+}
+
+#[test]
+fn check_rca_for_non_callable_analyzed_with_dynamic_params() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        newtype Pair = (First : Int, Second : Int);"#,
+    );
+    let package_id = compilation_context
+        .fir_store
+        .iter()
+        .map(|(package_id, _)| package_id)
+        .max()
+        .expect("at least one package should exist");
+    let udt_item_id = compilation_context
+        .fir_store
+        .get(package_id)
+        .items
+        .iter()
+        .find_map(|(item_id, item)| matches!(item.kind, ItemKind::Ty(..)).then_some(item_id))
+        .expect("type item should exist");
+    let mut analyzer = Analyzer::init_with_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties().clone(),
+    );
+    let compute_kind = analyzer.analyze_spec_with_dynamic_params(
+        (package_id, udt_item_id).into(),
+        &[InputParamIndex::from(0)],
+    );
+    assert!(compute_kind.is_none());
+}
+
+#[test]
+fn check_rca_completeness_verification_lists_blocks_missing_analysis() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Unit {
+            if M(q) == One {
+                X(q);
+            }
+        }"#,
+    );
+    let mut compute_properties = compilation_context.get_compute_properties().clone();
+    assert_eq!(
+        compute_properties.verify_completeness(&compilation_context.fir_store),
+        Ok(())
+    );
+
+    let foo_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let package_compute_properties = compute_properties.get_mut(foo_id.package);
+    let block_ids = package_compute_properties
+        .blocks
+        .iter()
+        .map(|(block_id, _)| block_id)
+        .collect::<Vec<_>>();
+    for block_id in &block_ids {
+        package_compute_properties.blocks.remove(*block_id);
+    }
+    assert_eq!(
+        compute_properties.verify_completeness(&compilation_context.fir_store),
+        Err(block_ids)
+    );
+}
+
+#[test]
+fn check_rca_for_operation_with_desugared_default_argument_bindings() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit, theta : Double, useDefaultTheta : Bool) : Unit {
+            // Bindings like the ones synthesized when lowering defaulted arguments.
+            let theta = useDefaultTheta ? 1.0 | theta;
+            let defaultPhi = 0.5;
+            Rx(theta + defaultPhi, q);
+        }"#,
+    );
+    let callable_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let ItemComputeProperties::Callable(callable_compute_properties) = compilation_context
+        .get_compute_properties()
+        .get_item(callable_id)
+    else {
+        panic!("item should be a callable");
+    };
+    assert_eq!(
+        callable_compute_properties
+            .body
+            .to_string()
+            .matches("[Parameter Type")
+            .count(),
+        3
+    );
+}
+
+#[test]
+fn check_rca_compact_string_for_operation_with_one_param() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Unit {
+            H(q);
+        }"#,
+    );
+    let callable_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let ItemComputeProperties::Callable(callable_compute_properties) = compilation_context
+        .get_compute_properties()
+        .get_item(callable_id)
+    else {
+        panic!("item should be a callable");
+    };
+    expect!["inherent=Q[]:Element(Static);p0=Q[UseOfDynamicQubit]:Element(Static)"]
+        .assert_eq(&callable_compute_properties.body.to_compact_string());
+}
+
+#[test]
+fn check_rca_for_entry_point_operation_and_its_capabilities() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Unit {
+            H(q);
+        }
+        @EntryPoint()
+        operation Main() : Result {
+            use q = Qubit();
+            let r = M(q);
+            if r == One {
+                Foo(q);
+            }
+            r
+        }"#,
+    );
+    let main_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Main")
+        .expect("callable should exist");
+    let package_compute_properties = compilation_context
+        .get_compute_properties()
+        .get(main_id.package);
+    assert_eq!(
+        package_compute_properties.entry_point(&compilation_context.fir_store, main_id.package),
+        Some(main_id)
+    );
+    let capabilities = package_compute_properties
+        .entry_point_capabilities(&compilation_context.fir_store, main_id.package)
+        .expect("entry point capabilities should exist");
+    assert!(capabilities.contains(RuntimeCapabilityFlags::ForwardBranching));
+}
+
+#[test]
+fn check_rca_for_precomputed_generator_set_applied_to_different_arguments() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        function Foo(a : Int, b : Int) : Int {
+            a + b
+        }"#,
+    );
+    let callable_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let ItemComputeProperties::Callable(callable_compute_properties) = compilation_context
+        .get_compute_properties()
+        .get_item(callable_id)
+    else {
+        panic!("item should be a callable");
+    };
+    let generator_set = &callable_compute_properties.body;
+
+    let compute_kind = generator_set.apply(&[
+        ValueKind::Element(RuntimeKind::Static),
+        ValueKind::Element(RuntimeKind::Static),
+    ]);
+    expect!["Classical"].assert_eq(&compute_kind.to_string());
+
+    let compute_kind = generator_set.apply(&[
+        ValueKind::Element(RuntimeKind::Static),
+        ValueKind::Element(RuntimeKind::Dynamic),
+    ]);
+    expect![[r#"
+        Quantum: QuantumProperties:
+            runtime_features: RuntimeFeatureFlags(UseOfDynamicInt | DynamicOperandInIntArithmetic)
+            value_kind: Element(Dynamic)"#]]
+    .assert_eq(&compute_kind.to_string());
+}
+
+#[test]
+fn check_rca_feature_budget_flags_feature_heavy_callable() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        open Microsoft.Quantum.Convert;
+        operation Foo() : Double {
+            use q = Qubit();
+            let n = M(q) == Zero ? 1 | 2;
+            IntAsDouble(n) * 2.0
+        }
+        operation Bar() : Unit {
+            use q = Qubit();
+            H(q);
+        }"#,
+    );
+    let foo_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let bar_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Bar")
+        .expect("callable should exist");
+    let over_budget_callables = compilation_context
+        .get_compute_properties()
+        .check_feature_budget(2)
+        .expect_err("budget should be exceeded");
+    assert!(over_budget_callables.contains(&foo_id));
+    assert!(!over_budget_callables.contains(&bar_id));
+}
+
+#[test]
+fn check_rca_feature_budget_counts_dynamic_param_application_features() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        function Foo(i : Int) : Int {
+            i + 1
+        }"#,
+    );
+    let foo_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+
+    // The function is inherently classical, but adding a dynamic argument uses two runtime features.
+    let over_budget_callables = compilation_context
+        .get_compute_properties()
+        .check_feature_budget(1)
+        .expect_err("budget should be exceeded");
+    assert!(over_budget_callables.contains(&foo_id));
+}
+
+#[test]
+fn check_rca_for_operation_with_empty_body() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Unit {}"#,
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Classical
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Classical
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_union_of_reachable_features_over_entry_points() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        function ClassicalEntry() : Int {
+            42
+        }
+        operation QuantumEntry() : Unit {
+            use q = Qubit();
+            if M(q) == One {
+                X(q);
+            }
+        }"#,
+    );
+    let classical_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("ClassicalEntry")
+        .expect("callable should exist");
+    let quantum_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("QuantumEntry")
+        .expect("callable should exist");
+    let compute_properties = compilation_context.get_compute_properties();
+    let quantum_features =
+        compute_properties.reachable_capabilities(quantum_id, &compilation_context.fir_store);
+    assert!(!quantum_features.is_empty());
+    assert_eq!(
+        compute_properties
+            .union_reachable(&[classical_id, quantum_id], &compilation_context.fir_store),
+        quantum_features
+    );
+}
+
+#[test]
+fn check_rca_for_effectively_classical_operations() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(a : Int, b : Int) : Int {
+            a * b + 1
+        }
+        operation Bar(q : Qubit) : Unit {
+            H(q);
+        }"#,
+    );
+    let body_of = |name: &str| {
+        let callable_id = compilation_context
+            .fir_store
+            .find_callable_id_by_name(name)
+            .expect("callable should exist");
+        let ItemComputeProperties::Callable(callable_compute_properties) = compilation_context
+            .get_compute_properties()
+            .get_item(callable_id)
+        else {
+            panic!("item should be a callable");
+        };
+        callable_compute_properties.body.clone()
+    };
+    assert!(body_of("Foo").is_effectively_classical());
+    assert!(!body_of("Bar").is_effectively_classical());
+}
+
+#[test]
+fn check_rca_for_callables_always_called_with_static_arguments() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        function Helper(i : Int) : Int {
+            i * 2
+        }
+        function Other(i : Int) : Int {
+            i + 1
+        }
+        operation Foo() : Int {
+            use q = Qubit();
+            let a = Helper(1);
+            let b = Helper(a);
+            let r = M(q) == One ? 1 | 0;
+            Other(b) + Other(r)
+        }"#,
+    );
+    let find = |name: &str| {
+        compilation_context
+            .fir_store
+            .find_callable_id_by_name(name)
+            .expect("callable should exist")
+    };
+    let always_static = compilation_context
+        .get_compute_properties()
+        .always_static_call_sites(&compilation_context.fir_store);
+    assert!(always_static.contains(&find("Helper")));
+    assert!(!always_static.contains(&find("Other")));
+}
+
+#[test]
+fn check_rca_for_operation_returning_dynamically_selected_operation() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation A(q : Qubit) : Unit {
+            X(q);
+        }
+        operation B(q : Qubit) : Unit {
+            Z(q);
+        }
+        operation Foo() : (Qubit => Unit) {
+            use q = Qubit();
+            if M(q) == One {
+                return A;
+            }
+            return B;
+        }"#,
+    );
+    let callable_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let ItemComputeProperties::Callable(callable_compute_properties) = compilation_context
+        .get_compute_properties()
+        .get_item(callable_id)
+    else {
+        panic!("item should be a callable");
+    };
+    let ComputeKind::Quantum(quantum_properties) = &callable_compute_properties.body.inherent
+    else {
+        panic!("compute kind should be quantum");
+    };
+    assert!(quantum_properties
+        .runtime_features
+        .contains(RuntimeFeatureFlags::UseOfDynamicArrowOperation));
+    assert!(matches!(
+        quantum_properties.value_kind,
+        ValueKind::Element(RuntimeKind::Dynamic)
+    ));
+}
+
+#[test]
+fn check_rca_for_max_dynamic_param_count() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit, i : Int, d : Double, b : Bool) : Unit {
+            if b {
+                Rx(d * IntAsDouble(i), q);
+            }
+        }"#,
+    );
+    let callable_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let compute_properties = compilation_context.get_compute_properties();
+    assert_eq!(
+        compute_properties
+            .get(callable_id.package)
+            .max_dynamic_param_count(),
+        4
+    );
+    assert!(compute_properties.max_dynamic_param_count() >= 4);
+}
+
+#[test]
+fn check_rca_for_operation_with_measurement_after_unconditional_return() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Unit {
+            H(q);
+            return ();
+            if M(q) == One {
+                X(q);
+            }
+        }"#,
+    );
+    let callable_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let ItemComputeProperties::Callable(callable_compute_properties) = compilation_context
+        .get_compute_properties()
+        .get_item(callable_id)
+    else {
+        panic!("item should be a callable");
+    };
+    assert_eq!(
+        callable_compute_properties.body.inherent.runtime_features(),
+        RuntimeFeatureFlags::empty()
+    );
+}
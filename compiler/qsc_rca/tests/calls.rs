@@ -6,7 +6,13 @@
 pub mod test_utils;
 
 use expect_test::expect;
-use test_utils::{check_last_statement_compute_properties, CompilationContext};
+use qsc_rca::{
+    Analyzer, ComputeKind, ComputePropertiesLookup, ItemComputeProperties, RuntimeFeatureFlags,
+};
+use test_utils::{
+    check_callable_compute_properties, check_last_statement_compute_properties, CompilationContext,
+    PackageStoreSearch,
+};
 
 #[test]
 fn check_rca_for_call_to_cyclic_function_with_classical_argument() {
@@ -159,15 +165,15 @@ fn check_rca_for_call_to_dynamic_closure_function() {
     );
     let package_store_compute_properties = compilation_context.get_compute_properties();
 
-    // Note that the "use of dynamic integer" runtime feature is missing because we are currently not performing
-    // detailed analysis on closures.
+    // Note that the closure is resolved, so the runtime features of applying it with the captured dynamic integer are
+    // included.
     check_last_statement_compute_properties(
         package_store_compute_properties,
         &expect![
             r#"
             ApplicationsGeneratorSet:
                 inherent: Quantum: QuantumProperties:
-                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | CallToDynamicCallee | UseOfClosure)
+                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicInt | CallToDynamicCallee | LoopWithDynamicCondition | UseOfClosure | DynamicOperandInIntArithmetic)
                     value_kind: Element(Dynamic)
                 dynamic_param_applications: <empty>"#
         ],
@@ -214,15 +220,15 @@ fn check_rca_for_call_to_dynamic_closure_operation() {
     );
     let package_store_compute_properties = compilation_context.get_compute_properties();
 
-    // Note that the "use of dynamic bool" and the "use of dynamic double" runtime features are missing because we are
-    // currently not performing detailed analysis on closures.
+    // Note that the closure is resolved, so the "use of dynamic double" runtime feature of applying it with the
+    // captured dynamic angle is included.
     check_last_statement_compute_properties(
         package_store_compute_properties,
         &expect![
             r#"
         ApplicationsGeneratorSet:
             inherent: Quantum: QuantumProperties:
-                runtime_features: RuntimeFeatureFlags(CallToDynamicCallee | UseOfClosure)
+                runtime_features: RuntimeFeatureFlags(UseOfDynamicDouble | CallToDynamicCallee | UseOfClosure)
                 value_kind: Element(Static)
             dynamic_param_applications: <empty>"#
         ],
@@ -250,9 +256,305 @@ fn check_rca_for_call_to_operation_with_one_classical_return_and_one_dynamic_ret
             r#"
             ApplicationsGeneratorSet:
                 inherent: Quantum: QuantumProperties:
-                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicInt | ForwardBranchingOnDynamicValue | ReturnWithinDynamicScope)
+                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicInt | ForwardBranchingOnDynamicValue | ReturnWithinDynamicScope | DynamicQubitRelease)
                     value_kind: Element(Dynamic)
                 dynamic_param_applications: <empty>"#
         ],
     );
 }
+
+#[test]
+fn check_rca_for_operation_that_only_applies_a_global_phase() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Unit {
+            R(PauliI, 1.0, q);
+        }"#,
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Classical
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(0x0)
+                            value_kind: Element(Static)
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_call_to_apply_if_one_with_dynamic_result() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation ApplyIfOne<'T>(result : Result, (op : ('T => Unit), target : 'T)) : Unit {
+            if result == One {
+                op(target);
+            }
+        }
+        operation Foo() : Unit {
+            use (control, target) = (Qubit(), Qubit());
+            ApplyIfOne(M(control), (X, target));
+        }"#,
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "ApplyIfOne",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(CallToUnresolvedCallee)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | CallToUnresolvedCallee | ForwardBranchingOnDynamicValue)
+                            value_kind: Element(Static)
+                        [1]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(CallToDynamicCallee)
+                            value_kind: Element(Static)
+                        [2]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(CallToUnresolvedCallee)
+                            value_kind: Element(Static)
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | CallToUnresolvedCallee | ForwardBranchingOnDynamicValue)
+                        value_kind: Element(Static)
+                    dynamic_param_applications: <empty>
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_new_top_level_call_reuses_callable_analysis() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo() : Unit {
+            use q = Qubit();
+            H(q);
+        }"#,
+    );
+
+    // Mark the already analyzed callable with a runtime feature it does not use, so that reusing its analysis instead
+    // of recomputing it is observable in the compute kind of the new statement.
+    let foo_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let mut compute_properties = compilation_context.get_compute_properties().clone();
+    let Some(ItemComputeProperties::Callable(foo_compute_properties)) = compute_properties
+        .get_mut(foo_id.package)
+        .items
+        .get_mut(foo_id.item)
+    else {
+        panic!("callable compute properties should exist");
+    };
+    let ComputeKind::Quantum(quantum_properties) = &mut foo_compute_properties.body.inherent else {
+        panic!("operation should be quantum");
+    };
+    quantum_properties.runtime_features |= RuntimeFeatureFlags::UseOfDynamicInt;
+
+    let package_id = compilation_context.lower_fragments("Foo();");
+    let stmt_id = compilation_context
+        .fir_store
+        .get(package_id)
+        .stmts
+        .iter()
+        .map(|(stmt_id, _)| stmt_id)
+        .max()
+        .expect("at least one statement should exist");
+    let mut analyzer =
+        Analyzer::init_with_compute_properties(&compilation_context.fir_store, compute_properties);
+    let compute_kind = analyzer.analyze_new_top_level_stmt(package_id, stmt_id);
+    expect![[r#"
+        Quantum: QuantumProperties:
+            runtime_features: RuntimeFeatureFlags(UseOfDynamicInt)
+            value_kind: Element(Static)"#]]
+    .assert_eq(&compute_kind.to_string());
+}
+
+#[test]
+fn check_rca_for_call_with_mutable_array_argument_made_dynamic_by_update() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        function Sum(values : Int[]) : Int {
+            mutable sum = 0;
+            for value in values {
+                set sum += value;
+            }
+            sum
+        }
+        use q = Qubit();
+        mutable values = [0, size = 3];
+        set values w/= 1 <- M(q) == One ? 1 | 0;
+        Sum(values)"#,
+    );
+    let package_store_compute_properties = compilation_context.get_compute_properties();
+
+    // Updating an element only makes the content of the array dynamic, so the dynamic content and static size
+    // application of the callee is selected, which does not use a dynamically sized array or a dynamic loop.
+    check_last_statement_compute_properties(
+        package_store_compute_properties,
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicInt | DynamicOperandInIntArithmetic)
+                    value_kind: Element(Dynamic)
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_new_top_level_call_after_analyzer_reset() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo() : Unit {
+            use q = Qubit();
+            H(q);
+        }"#,
+    );
+
+    // Mark the already analyzed callable with a runtime feature it does not use, so that resetting the analyzer is
+    // observable in the compute kind of the new statement once the callable gets reanalyzed.
+    let foo_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let mut compute_properties = compilation_context.get_compute_properties().clone();
+    let Some(ItemComputeProperties::Callable(foo_compute_properties)) = compute_properties
+        .get_mut(foo_id.package)
+        .items
+        .get_mut(foo_id.item)
+    else {
+        panic!("callable compute properties should exist");
+    };
+    let ComputeKind::Quantum(quantum_properties) = &mut foo_compute_properties.body.inherent else {
+        panic!("operation should be quantum");
+    };
+    quantum_properties.runtime_features |= RuntimeFeatureFlags::UseOfDynamicInt;
+
+    let package_id = compilation_context.lower_fragments("Foo();");
+    let stmt_id = compilation_context
+        .fir_store
+        .get(package_id)
+        .stmts
+        .iter()
+        .map(|(stmt_id, _)| stmt_id)
+        .max()
+        .expect("at least one statement should exist");
+    let mut analyzer =
+        Analyzer::init_with_compute_properties(&compilation_context.fir_store, compute_properties);
+    let compute_kind = analyzer.analyze_new_top_level_stmt(package_id, stmt_id);
+    expect![[r#"
+        Quantum: QuantumProperties:
+            runtime_features: RuntimeFeatureFlags(UseOfDynamicInt)
+            value_kind: Element(Static)"#]]
+    .assert_eq(&compute_kind.to_string());
+
+    analyzer.reset();
+    let compute_kind = analyzer.analyze_new_top_level_stmt(package_id, stmt_id);
+    expect![[r#"
+        Quantum: QuantumProperties:
+            runtime_features: RuntimeFeatureFlags(0x0)
+            value_kind: Element(Static)"#]]
+    .assert_eq(&compute_kind.to_string());
+}
+
+#[test]
+fn check_rca_for_controlled_call_with_dynamically_selected_control_qubit() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo() : Unit {
+            use (qs, target) = (Qubit[2], Qubit());
+            let i = M(qs[0]) == One ? 0 | 1;
+            Controlled X([qs[i]], target);
+        }"#,
+    );
+    let foo_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let ItemComputeProperties::Callable(foo_compute_properties) = compilation_context
+        .get_compute_properties()
+        .get_item(foo_id)
+    else {
+        panic!("item should be a callable");
+    };
+    let ComputeKind::Quantum(quantum_properties) = &foo_compute_properties.body.inherent else {
+        panic!("operation should be quantum");
+    };
+    assert!(quantum_properties
+        .runtime_features
+        .contains(RuntimeFeatureFlags::UseOfDynamicInt | RuntimeFeatureFlags::UseOfDynamicQubit));
+}
+
+#[test]
+fn check_rca_for_unresolved_callee_in_strict_and_lenient_modes() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        function Apply(f : Int -> Int, x : Int) : Int {
+            f(x)
+        }"#,
+    );
+    let apply_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Apply")
+        .expect("callable should exist");
+
+    let unresolved_callees = Analyzer::init(&compilation_context.fir_store)
+        .with_strict_callee_resolution()
+        .try_analyze_all()
+        .expect_err("strict analysis should fail");
+    assert!(unresolved_callees
+        .iter()
+        .any(|callee_id| callee_id.package == apply_id.package));
+
+    let compute_properties = Analyzer::init(&compilation_context.fir_store)
+        .try_analyze_all()
+        .expect("lenient analysis should succeed");
+    let ItemComputeProperties::Callable(apply_compute_properties) =
+        compute_properties.get_item(apply_id)
+    else {
+        panic!("item should be a callable");
+    };
+    let ComputeKind::Quantum(quantum_properties) = &apply_compute_properties.body.inherent else {
+        panic!("call to an unresolved callee should be quantum");
+    };
+    assert!(quantum_properties
+        .runtime_features
+        .contains(RuntimeFeatureFlags::CallToUnresolvedCallee));
+}
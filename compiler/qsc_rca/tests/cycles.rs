@@ -6,7 +6,9 @@
 pub mod test_utils;
 
 use expect_test::expect;
-use test_utils::{check_callable_compute_properties, CompilationContext};
+use qsc_fir::ty::FunctorSetValue;
+use qsc_rca::{ComputePropertiesLookup, ItemComputeProperties};
+use test_utils::{check_callable_compute_properties, CompilationContext, PackageStoreSearch};
 
 #[test]
 fn check_rca_for_one_function_cycle() {
@@ -559,7 +561,7 @@ fn check_rca_for_function_cycle_within_while_block() {
                     inherent: Classical
                     dynamic_param_applications:
                         [0]: [Parameter Type Element] Quantum: QuantumProperties:
-                            runtime_features: RuntimeFeatureFlags(CallToCyclicFunctionWithDynamicArg)
+                            runtime_features: RuntimeFeatureFlags(CallToCyclicFunctionWithDynamicArg | PossiblyNonTerminating)
                             value_kind: Element(Dynamic)
                 adj: <none>
                 ctl: <none>
@@ -906,3 +908,203 @@ fn check_rca_for_operation_multi_controlled_functor_recursion() {
         ],
     );
 }
+
+#[test]
+fn check_rca_reports_recursive_function_as_analyzed_from_cycle_assumption() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        function Foo(i : Int) : Int {
+            i > 0 ? Foo(i - 1) | 0
+        }"#,
+    );
+    let callable_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let ItemComputeProperties::Callable(callable_compute_properties) = compilation_context
+        .get_compute_properties()
+        .get_item(callable_id)
+    else {
+        panic!("item should be a callable");
+    };
+    assert!(callable_compute_properties.body.is_from_cycle_assumption());
+}
+
+#[test]
+fn check_rca_reports_straight_line_function_as_not_analyzed_from_cycle_assumption() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        function Foo(i : Int) : Int {
+            let j = i + 1;
+            j * 2
+        }"#,
+    );
+    let callable_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let ItemComputeProperties::Callable(callable_compute_properties) = compilation_context
+        .get_compute_properties()
+        .get_item(callable_id)
+    else {
+        panic!("item should be a callable");
+    };
+    assert!(!callable_compute_properties.body.is_from_cycle_assumption());
+}
+
+#[test]
+fn check_rca_for_mutually_recursive_closures() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        function Even(n : Int) : Bool {
+            let isOdd = m -> Odd(m);
+            let check = m -> m == 0 or isOdd(m - 1);
+            check(n)
+        }
+        function Odd(n : Int) : Bool {
+            let isEven = m -> Even(m);
+            n != 0 and isEven(n - 1)
+        }"#,
+    );
+
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Even",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Classical
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(CallToCyclicFunctionWithDynamicArg)
+                            value_kind: Element(Dynamic)
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Odd",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Classical
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(CallToCyclicFunctionWithDynamicArg)
+                            value_kind: Element(Dynamic)
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_displays_specializations_in_functor_set_value_display_order() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Unit is Adj + Ctl {
+            body ... {
+                Adjoint Foo(q);
+            }
+            adjoint ... {
+                Controlled Foo([], q);
+            }
+            controlled (_, ...) {
+                Foo(q);
+            }
+            controlled adjoint (_, ...) {
+                Foo(q);
+            }
+        }"#,
+    );
+    let callable_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let displayed = compilation_context
+        .get_compute_properties()
+        .get_item(callable_id)
+        .to_string();
+    let positions = FunctorSetValue::display_order().map(|functor_set_value| {
+        let label = match functor_set_value {
+            FunctorSetValue::Empty => "\n    body: ",
+            FunctorSetValue::Adj => "\n    adj: ",
+            FunctorSetValue::Ctl => "\n    ctl: ",
+            FunctorSetValue::CtlAdj => "\n    ctl-adj: ",
+        };
+        displayed
+            .find(label)
+            .expect("specialization should be displayed")
+    });
+    assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn check_rca_quantum_source_specializations_include_measurements_and_cyclic_operations() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Result {
+            Foo(q)
+        }
+        function Bar(i : Int) : Int {
+            i + 1
+        }"#,
+    );
+    let quantum_source_specializations = compilation_context
+        .get_compute_properties()
+        .quantum_source_specializations();
+    let is_quantum_source = |name: &str| {
+        let callable_id = compilation_context
+            .fir_store
+            .find_callable_id_by_name(name)
+            .expect("callable should exist");
+        quantum_source_specializations.contains(&(callable_id, FunctorSetValue::Empty).into())
+    };
+    assert!(is_quantum_source("M"));
+    assert!(is_quantum_source("Foo"));
+    assert!(!is_quantum_source("Bar"));
+}
+
+#[test]
+fn check_rca_for_operation_recursion_through_array_element() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Unit {
+            let ops = [Foo];
+            ops[0](q);
+        }"#,
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(CyclicOperationSpec)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(CyclicOperationSpec)
+                            value_kind: Element(Static)
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
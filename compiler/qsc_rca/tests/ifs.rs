@@ -6,8 +6,11 @@
 pub mod test_utils;
 
 use expect_test::expect;
+use qsc_fir::fir::{CallableImpl, ItemKind, PackageLookup};
+use qsc_rca::RuntimeFeatureFlags;
 use test_utils::{
     check_callable_compute_properties, check_last_statement_compute_properties, CompilationContext,
+    PackageStoreSearch,
 };
 
 #[test]
@@ -121,3 +124,94 @@ fn check_rca_for_if_else_expr_with_dynamic_condition_and_classic_branch_blocks()
         ],
     );
 }
+
+#[test]
+fn check_rca_max_dynamic_scope_depth_for_triply_nested_dynamic_ifs() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo() : Unit {
+            use (q0, q1, q2) = (Qubit(), Qubit(), Qubit());
+            if M(q0) == Zero {
+                if M(q1) == Zero {
+                    if M(q2) == Zero {
+                        X(q0);
+                    }
+                }
+            }
+        }"#,
+    );
+    let callable_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let package_compute_properties = compilation_context
+        .get_compute_properties()
+        .get(callable_id.package);
+    assert_eq!(
+        package_compute_properties.max_dynamic_scope_depth(callable_id),
+        3
+    );
+}
+
+#[test]
+fn check_rca_block_features_for_measurement_conditioned_branch() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Unit {
+            if M(q) == One {
+                X(q);
+            }
+        }"#,
+    );
+    let foo_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let package = compilation_context.fir_store.get(foo_id.package);
+    let ItemKind::Callable(foo_decl) = &package.get_item(foo_id.item).kind else {
+        panic!("item should be a callable");
+    };
+    let CallableImpl::Spec(spec_impl) = &foo_decl.implementation else {
+        panic!("callable should have specializations");
+    };
+    let block_features = compilation_context
+        .get_compute_properties()
+        .get(foo_id.package)
+        .block_features(spec_impl.body.block);
+    assert!(block_features.contains(RuntimeFeatureFlags::ForwardBranchingOnDynamicValue));
+}
+
+#[test]
+fn check_rca_for_if_else_expr_with_dynamic_condition_and_failing_branch_block() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        function Foo(r : Result) : Int {
+            if r == One {
+                1
+            } else {
+                fail "unexpected result"
+            }
+        }"#,
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Classical
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicBool)
+                            value_kind: Element(Static)
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
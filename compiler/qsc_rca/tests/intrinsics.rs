@@ -6,7 +6,12 @@
 pub mod test_utils;
 
 use expect_test::expect;
-use test_utils::{check_callable_compute_properties, CompilationContext};
+use qsc_fir::fir::CallableDecl;
+use qsc_rca::{
+    Analyzer, ComputePropertiesLookup, ExtendedFeatures, ItemComputeProperties, RuntimeKind,
+    ValueKind,
+};
+use test_utils::{check_callable_compute_properties, CompilationContext, PackageStoreSearch};
 
 #[test]
 fn check_rca_for_quantum_rt_qubit_allocate() {
@@ -95,13 +100,13 @@ fn check_rca_for_length() {
                     dynamic_param_applications:
                         [0]: [Parameter Type Array] ArrayParamApplication:
                             static_content_dynamic_size: Quantum: QuantumProperties:
-                                runtime_features: RuntimeFeatureFlags(UseOfDynamicallySizedArray)
+                                runtime_features: RuntimeFeatureFlags(UseOfDynamicInt | UseOfDynamicallySizedArray)
                                 value_kind: Element(Dynamic)
                             dynamic_content_static_size: Quantum: QuantumProperties:
                                 runtime_features: RuntimeFeatureFlags(0x0)
                                 value_kind: Element(Static)
                             dynamic_content_dynamic_size: Quantum: QuantumProperties:
-                                runtime_features: RuntimeFeatureFlags(UseOfDynamicallySizedArray)
+                                runtime_features: RuntimeFeatureFlags(UseOfDynamicInt | UseOfDynamicallySizedArray)
                                 value_kind: Element(Dynamic)
                 adj: <none>
                 ctl: <none>
@@ -149,7 +154,7 @@ fn check_rca_for_int_as_double() {
                     inherent: Classical
                     dynamic_param_applications:
                         [0]: [Parameter Type Element] Quantum: QuantumProperties:
-                            runtime_features: RuntimeFeatureFlags(UseOfDynamicInt)
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicDouble)
                             value_kind: Element(Dynamic)
                 adj: <none>
                 ctl: <none>
@@ -172,7 +177,7 @@ fn check_rca_for_int_as_big_int() {
                     inherent: Classical
                     dynamic_param_applications:
                         [0]: [Parameter Type Element] Quantum: QuantumProperties:
-                            runtime_features: RuntimeFeatureFlags(UseOfDynamicInt)
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicBigInt)
                             value_kind: Element(Dynamic)
                 adj: <none>
                 ctl: <none>
@@ -542,7 +547,7 @@ fn check_rca_for_truncate() {
                     inherent: Classical
                     dynamic_param_applications:
                         [0]: [Parameter Type Element] Quantum: QuantumProperties:
-                            runtime_features: RuntimeFeatureFlags(UseOfDynamicDouble)
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicInt)
                             value_kind: Element(Dynamic)
                 adj: <none>
                 ctl: <none>
@@ -551,6 +556,29 @@ fn check_rca_for_truncate() {
     );
 }
 
+#[test]
+fn check_rca_for_truncate_applied_to_dynamic_double() {
+    let compilation_context = CompilationContext::default();
+    let truncate_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Truncate")
+        .expect("callable should exist");
+    let ItemComputeProperties::Callable(truncate_compute_properties) = compilation_context
+        .get_compute_properties()
+        .get_item(truncate_id)
+    else {
+        panic!("item should be a callable");
+    };
+    let compute_kind = truncate_compute_properties
+        .body
+        .apply(&[ValueKind::Element(RuntimeKind::Dynamic)]);
+    expect![[r#"
+        Quantum: QuantumProperties:
+            runtime_features: RuntimeFeatureFlags(UseOfDynamicInt)
+            value_kind: Element(Dynamic)"#]]
+    .assert_eq(&compute_kind.to_string());
+}
+
 #[test]
 fn check_rca_for_quantum_qis_ccx_body() {
     let compilation_context = CompilationContext::default();
@@ -1289,3 +1317,157 @@ fn check_rca_for_end_repeat_estimates_internal() {
         ],
     );
 }
+
+#[test]
+fn check_rca_for_custom_intrinsic_with_extended_features() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation ECR(q0 : Qubit, q1 : Qubit) : Unit {
+            body intrinsic;
+        }"#,
+    );
+    let hook = |decl: &CallableDecl| {
+        if decl.name.name.as_ref() == "ECR" {
+            ExtendedFeatures::named("nativeECR")
+        } else {
+            ExtendedFeatures::empty()
+        }
+    };
+    let compute_properties = Analyzer::init(&compilation_context.fir_store)
+        .with_intrinsic_features_hook(&hook)
+        .analyze_all();
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        &compute_properties,
+        "ECR",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(0x0)
+                        extended_features: ExtendedFeatures(nativeECR)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicQubit)
+                            value_kind: Element(Static)
+                        [1]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicQubit)
+                            value_kind: Element(Static)
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_debug_only_message_with_dynamic_string() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo() : Unit {
+            use q = Qubit();
+            Message($"{M(q)}");
+        }"#,
+    );
+    let hook = |decl: &CallableDecl| matches!(decl.name.name.as_ref(), "Message" | "DumpMachine");
+    let compute_properties = Analyzer::init(&compilation_context.fir_store)
+        .with_debug_only_intrinsics_hook(&hook)
+        .analyze_all();
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        &compute_properties,
+        "Message",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Classical
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Classical
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        &compute_properties,
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicString)
+                        value_kind: Element(Static)
+                    dynamic_param_applications: <empty>
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_custom_intrinsic_returning_nested_result_array() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation MeasureGrid(qs : Qubit[]) : Result[][] {
+            body intrinsic;
+        }
+        operation Foo() : Bool {
+            use qs = Qubit[2];
+            let grid = MeasureGrid(qs);
+            grid[0][0] == One
+        }"#,
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "MeasureGrid",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(0x0)
+                        value_kind: Array(Content: Array(Content: Dynamic, Size: Dynamic), Size: Dynamic)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Array] ArrayParamApplication:
+                            static_content_dynamic_size: Quantum: QuantumProperties:
+                                runtime_features: RuntimeFeatureFlags(UseOfDynamicQubit | UseOfDynamicallySizedArray)
+                                value_kind: Array(Content: Array(Content: Dynamic, Size: Dynamic), Size: Dynamic)
+                            dynamic_content_static_size: Quantum: QuantumProperties:
+                                runtime_features: RuntimeFeatureFlags(UseOfDynamicQubit | UseOfDynamicallySizedArray)
+                                value_kind: Array(Content: Array(Content: Dynamic, Size: Dynamic), Size: Dynamic)
+                            dynamic_content_dynamic_size: Quantum: QuantumProperties:
+                                runtime_features: RuntimeFeatureFlags(UseOfDynamicQubit | UseOfDynamicallySizedArray)
+                                value_kind: Array(Content: Array(Content: Dynamic, Size: Dynamic), Size: Dynamic)
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicallySizedArray)
+                        value_kind: Element(Dynamic)
+                    dynamic_param_applications: <empty>
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
@@ -0,0 +1,81 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT License.
+
+#![allow(clippy::needless_raw_string_hashes)]
+
+pub mod test_utils;
+
+use expect_test::expect;
+use qsc_eval::debug::map_hir_package_to_fir;
+use test_utils::CompilationContext;
+
+#[test]
+fn check_rca_lsp_json_for_operation_with_measurement() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo() : Bool {
+            use q = Qubit();
+            M(q) == One
+        }"#,
+    );
+    let package_id = map_hir_package_to_fir(compilation_context.compiler.package_id());
+    let json = compilation_context
+        .get_compute_properties()
+        .get(package_id)
+        .to_lsp_json(&compilation_context.fir_store, package_id);
+    expect![[r#"
+        [
+          {
+            "dynamic": false,
+            "features": [],
+            "span": {
+              "end": 62,
+              "start": 46
+            }
+          },
+          {
+            "dynamic": false,
+            "features": [],
+            "span": {
+              "end": 76,
+              "start": 75
+            }
+          },
+          {
+            "dynamic": true,
+            "features": [],
+            "span": {
+              "end": 79,
+              "start": 75
+            }
+          },
+          {
+            "dynamic": true,
+            "features": [
+              "UseOfDynamicBool"
+            ],
+            "span": {
+              "end": 86,
+              "start": 75
+            }
+          },
+          {
+            "dynamic": false,
+            "features": [],
+            "span": {
+              "end": 78,
+              "start": 77
+            }
+          },
+          {
+            "dynamic": false,
+            "features": [],
+            "span": {
+              "end": 86,
+              "start": 83
+            }
+          }
+        ]"#]]
+    .assert_eq(&serde_json::to_string_pretty(&json).expect("json should serialize"));
+}
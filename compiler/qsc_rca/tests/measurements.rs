@@ -6,7 +6,17 @@
 pub mod test_utils;
 
 use expect_test::expect;
-use test_utils::{check_last_statement_compute_properties, CompilationContext};
+use qsc_fir::{
+    fir::{CallableImpl, ExprKind, ItemKind, PackageLookup},
+    ty::{Prim, Ty},
+};
+use qsc_rca::{
+    Analyzer, ComputeKind, ComputePropertiesLookup, ItemComputeProperties, RuntimeFeatureFlags,
+};
+use test_utils::{
+    check_callable_compute_properties, check_last_statement_compute_properties, CompilationContext,
+    PackageStoreSearch,
+};
 
 #[test]
 fn check_rca_for_static_single_qubit_measurement() {
@@ -128,3 +138,346 @@ fn check_rca_for_static_multi_qubit_measurement() {
         ],
     );
 }
+
+#[test]
+fn check_rca_for_standalone_measurement_sub_expression() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+            use q = Qubit();
+            let isZero = M(q) == Zero;"#,
+    );
+    let package_id = compilation_context
+        .fir_store
+        .iter()
+        .map(|(package_id, _)| package_id)
+        .max()
+        .expect("at least one package should exist");
+    let package = compilation_context.fir_store.get(package_id);
+    let measurement_expr_id = package
+        .exprs
+        .iter()
+        .find_map(|(expr_id, expr)| {
+            (matches!(expr.kind, ExprKind::Call(..)) && expr.ty == Ty::Prim(Prim::Result))
+                .then_some(expr_id)
+        })
+        .expect("measurement expression should exist");
+    let mut analyzer = Analyzer::init_with_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties().clone(),
+    );
+    let compute_kind = analyzer.analyze_expr_standalone(package_id, measurement_expr_id);
+    expect![[r#"
+        Quantum: QuantumProperties:
+            runtime_features: RuntimeFeatureFlags(0x0)
+            value_kind: Element(Dynamic)"#]]
+    .assert_eq(&compute_kind.to_string());
+}
+
+#[test]
+fn check_rca_for_discarded_measurement_within_dynamic_scope() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo() : Unit {
+            use (condition, target) = (Qubit(), Qubit());
+            if M(condition) == Zero {
+                M(target);
+            }
+        }"#,
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | ForwardBranchingOnDynamicValue | DynamicResultAllocation)
+                        value_kind: Element(Static)
+                    dynamic_param_applications: <empty>
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_collects_runtime_features_used_across_store() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo() : Double {
+            use q = Qubit();
+            let angle = M(q) == Zero ? 1.0 | 2.0;
+            angle * 2.0
+        }"#,
+    );
+    let (_, runtime_features) =
+        Analyzer::init(&compilation_context.fir_store).analyze_all_collecting();
+    assert!(runtime_features
+        .contains(RuntimeFeatureFlags::UseOfDynamicBool | RuntimeFeatureFlags::UseOfDynamicDouble));
+}
+
+#[test]
+fn check_rca_for_adjoint_of_measuring_operation_without_adj_specialization() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Unit is Adj {
+            body ... {
+                let isOne = M(q) == One;
+            }
+            adjoint self;
+        }
+        operation Bar(q : Qubit) : Unit {
+            Adjoint Foo(q);
+        }"#,
+    );
+
+    // A measuring operation cannot be adjointed, so an erroneous `is Adj` declaration leaves the callable without an
+    // adjoint specialization. Simulate it by removing the adjoint specialization along with its statements.
+    let foo_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let package = compilation_context.fir_store.get_mut(foo_id.package);
+    let foo_item = package
+        .items
+        .get_mut(foo_id.item)
+        .expect("item should exist");
+    let ItemKind::Callable(foo_decl) = &mut foo_item.kind else {
+        panic!("item should be a callable");
+    };
+    let CallableImpl::Spec(spec_impl) = &mut foo_decl.implementation else {
+        panic!("callable should have specializations");
+    };
+    let adj_decl = spec_impl
+        .adj
+        .take()
+        .expect("adj specialization should exist");
+    let adj_stmts = package.get_block(adj_decl.block).stmts.clone();
+    for stmt_id in adj_stmts {
+        package.stmts.remove(stmt_id);
+    }
+
+    let compute_properties = Analyzer::init(&compilation_context.fir_store).analyze_all();
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        &compute_properties,
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicQubit)
+                            value_kind: Element(Static)
+                adj: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicQubit)
+                            value_kind: Element(Static)
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_controlled_adjoint_of_measuring_operation_without_ctl_adj_specialization() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo(q : Qubit) : Unit is Adj + Ctl {
+            body ... {
+                let isOne = M(q) == One;
+            }
+            adjoint self;
+            controlled (_, ...) {
+                let isOne = M(q) == One;
+            }
+            controlled adjoint (_, ...) {
+                let isOne = M(q) == One;
+            }
+        }
+        operation Bar(q : Qubit) : Unit {
+            Controlled Adjoint Foo([], q);
+        }"#,
+    );
+
+    // Simulate an erroneous `is Adj + Ctl` declaration that leaves the callable without a controlled adjoint
+    // specialization by removing it along with its statements.
+    let foo_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let package = compilation_context.fir_store.get_mut(foo_id.package);
+    let foo_item = package
+        .items
+        .get_mut(foo_id.item)
+        .expect("item should exist");
+    let ItemKind::Callable(foo_decl) = &mut foo_item.kind else {
+        panic!("item should be a callable");
+    };
+    let CallableImpl::Spec(spec_impl) = &mut foo_decl.implementation else {
+        panic!("callable should have specializations");
+    };
+    let ctl_adj_decl = spec_impl
+        .ctl_adj
+        .take()
+        .expect("ctl_adj specialization should exist");
+    let ctl_adj_stmts = package.get_block(ctl_adj_decl.block).stmts.clone();
+    for stmt_id in ctl_adj_stmts {
+        package.stmts.remove(stmt_id);
+    }
+
+    let compute_properties = Analyzer::init(&compilation_context.fir_store).analyze_all();
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        &compute_properties,
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicQubit)
+                            value_kind: Element(Static)
+                adj: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicQubit)
+                            value_kind: Element(Static)
+                ctl: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicQubit)
+                            value_kind: Element(Static)
+                ctl-adj: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool)
+                        value_kind: Element(Static)
+                    dynamic_param_applications:
+                        [0]: [Parameter Type Element] Quantum: QuantumProperties:
+                            runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicQubit)
+                            value_kind: Element(Static)"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_measurement_in_dynamic_pauli_basis() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo() : Result {
+            use qs = Qubit[2];
+            let basis = M(qs[0]) == Zero ? PauliX | PauliZ;
+            Measure([basis], [qs[1]])
+        }"#,
+    );
+    let foo_id = compilation_context
+        .fir_store
+        .find_callable_id_by_name("Foo")
+        .expect("callable should exist");
+    let ItemComputeProperties::Callable(foo_compute_properties) = compilation_context
+        .get_compute_properties()
+        .get_item(foo_id)
+    else {
+        panic!("item should be a callable");
+    };
+    let ComputeKind::Quantum(quantum_properties) = &foo_compute_properties.body.inherent else {
+        panic!("operation should be quantum");
+    };
+    assert!(quantum_properties
+        .runtime_features
+        .contains(RuntimeFeatureFlags::UseOfDynamicPauli));
+}
+
+#[test]
+fn check_rca_for_mid_circuit_measurement_usage() {
+    let uses_mid_circuit_measurement = |source: &str| {
+        let mut compilation_context = CompilationContext::default();
+        compilation_context.update(source);
+        compilation_context
+            .get_compute_properties()
+            .uses_mid_circuit_measurement(&compilation_context.fir_store)
+    };
+    assert!(uses_mid_circuit_measurement(
+        r#"
+        @EntryPoint()
+        operation Main() : Result {
+            use (q0, q1) = (Qubit(), Qubit());
+            if M(q0) == One {
+                X(q1);
+            }
+            M(q1)
+        }"#
+    ));
+    assert!(!uses_mid_circuit_measurement(
+        r#"
+        operation ApplyH(q : Qubit) : Unit is Ctl {
+            H(q);
+        }
+        @EntryPoint()
+        operation Main() : Result[] {
+            use (q0, q1) = (Qubit(), Qubit());
+            ApplyH(q0);
+            CNOT(q0, q1);
+            [M(q0), M(q1)]
+        }"#
+    ));
+    assert!(uses_mid_circuit_measurement(
+        r#"
+        @EntryPoint()
+        operation Main() : Bool {
+            use q = Qubit();
+            M(q) == One
+        }"#
+    ));
+}
+
+#[test]
+fn check_rca_for_mid_circuit_measurement_usage_without_entry_point() {
+    let uses_mid_circuit_measurement = |source: &str| {
+        let mut compilation_context = CompilationContext::default();
+        compilation_context.update(source);
+        compilation_context
+            .get_compute_properties()
+            .uses_mid_circuit_measurement(&compilation_context.fir_store)
+    };
+    assert!(uses_mid_circuit_measurement(
+        r#"
+        use (q0, q1) = (Qubit(), Qubit());
+        if M(q0) == One {
+            X(q1);
+        }"#
+    ));
+    assert!(!uses_mid_circuit_measurement(
+        r#"
+        use (q0, q1) = (Qubit(), Qubit());
+        CNOT(q0, q1);
+        let results = [M(q0), M(q1)];"#
+    ));
+}
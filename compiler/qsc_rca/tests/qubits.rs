@@ -6,8 +6,10 @@
 pub mod test_utils;
 
 use expect_test::expect;
+use qsc_rca::{ComputePropertiesLookup, ItemComputeProperties, RuntimeFeatureFlags};
 use test_utils::{
     check_callable_compute_properties, check_last_statement_compute_properties, CompilationContext,
+    PackageStoreSearch,
 };
 
 #[test]
@@ -57,7 +59,7 @@ fn check_rca_for_dynamic_single_qubit_allcation() {
             Callable: CallableComputeProperties:
                 body: ApplicationsGeneratorSet:
                     inherent: Quantum: QuantumProperties:
-                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicQubit | ForwardBranchingOnDynamicValue)
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicQubit | ForwardBranchingOnDynamicValue | DynamicQubitRelease)
                         value_kind: Element(Static)
                     dynamic_param_applications: <empty>
                 adj: <none>
@@ -106,9 +108,132 @@ fn check_rca_for_dynamic_multi_qubit_allcation() {
             r#"
             ApplicationsGeneratorSet:
                 inherent: Quantum: QuantumProperties:
-                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicInt | UseOfDynamicRange | UseOfDynamicQubit | UseOfDynamicallySizedArray | ForwardBranchingOnDynamicValue | LoopWithDynamicCondition)
+                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicInt | UseOfDynamicRange | UseOfDynamicQubit | UseOfDynamicallySizedArray | ForwardBranchingOnDynamicValue | LoopWithDynamicCondition | DynamicOperandInIntArithmetic)
                     value_kind: Array(Content: Dynamic, Size: Dynamic)
                 dynamic_param_applications: <empty>"#
         ],
     );
 }
+
+#[test]
+fn check_rca_for_dynamic_multi_qubit_allocation_within_dynamic_scope() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo() : Unit {
+            use q = Qubit();
+            let registerSize = M(q) == Zero ? 1 | 2;
+            if M(q) == One {
+                use register = Qubit[registerSize];
+            }
+        }"#,
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicInt | UseOfDynamicRange | UseOfDynamicQubit | UseOfDynamicallySizedArray | ForwardBranchingOnDynamicValue | LoopWithDynamicCondition | DynamicQubitRelease | DynamicOperandInIntArithmetic)
+                        value_kind: Element(Static)
+                    dynamic_param_applications: <empty>
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_operation_returning_qubit_allocated_within_dynamic_scope() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation Foo() : Qubit {
+            use control = Qubit();
+            mutable target = QIR.Runtime.__quantum__rt__qubit_allocate();
+            if M(control) == One {
+                QIR.Runtime.__quantum__rt__qubit_release(target);
+                set target = QIR.Runtime.__quantum__rt__qubit_allocate();
+            }
+            target
+        }"#,
+    );
+    check_callable_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties(),
+        "Foo",
+        &expect![
+            r#"
+            Callable: CallableComputeProperties:
+                body: ApplicationsGeneratorSet:
+                    inherent: Quantum: QuantumProperties:
+                        runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicQubit | ForwardBranchingOnDynamicValue | DynamicQubitRelease)
+                        value_kind: Element(Dynamic)
+                    dynamic_param_applications: <empty>
+                adj: <none>
+                ctl: <none>
+                ctl-adj: <none>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_static_size_register_allocation_within_operation() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        operation StaticRegister() : Unit {
+            use qs = Qubit[4];
+            ApplyToEach(H, qs);
+        }
+        operation DynamicRegister() : Unit {
+            use q = Qubit();
+            let n = M(q) == Zero ? 2 | 4;
+            use qs = Qubit[n];
+            ApplyToEach(H, qs);
+        }"#,
+    );
+    let runtime_features_of = |name: &str| {
+        let callable_id = compilation_context
+            .fir_store
+            .find_callable_id_by_name(name)
+            .expect("callable should exist");
+        let ItemComputeProperties::Callable(callable_compute_properties) = compilation_context
+            .get_compute_properties()
+            .get_item(callable_id)
+        else {
+            panic!("item should be a callable");
+        };
+        callable_compute_properties.body.inherent.runtime_features()
+    };
+    assert!(!runtime_features_of("StaticRegister")
+        .contains(RuntimeFeatureFlags::UseOfDynamicallySizedArray));
+    assert!(runtime_features_of("DynamicRegister")
+        .contains(RuntimeFeatureFlags::UseOfDynamicallySizedArray));
+}
+
+#[test]
+fn check_rca_for_static_size_register_value_kind() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        use register = Qubit[4];
+        register"#,
+    );
+    let package_store_compute_properties = compilation_context.get_compute_properties();
+    check_last_statement_compute_properties(
+        package_store_compute_properties,
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(0x0)
+                    value_kind: Array(Content: Static, Size: Static)
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+}
@@ -5,7 +5,7 @@ use expect_test::Expect;
 use qsc::incremental::Compiler;
 use qsc_data_structures::language_features::LanguageFeatures;
 use qsc_eval::{debug::map_hir_package_to_fir, lower::Lowerer};
-use qsc_fir::fir::{ItemKind, LocalItemId, Package, PackageStore, StoreItemId};
+use qsc_fir::fir::{ItemKind, LocalItemId, Package, PackageId, PackageStore, StoreItemId};
 use qsc_frontend::compile::{PackageStore as HirPackageStore, RuntimeCapabilityFlags, SourceMap};
 use qsc_passes::PackageType;
 use qsc_rca::{Analyzer, ComputePropertiesLookup, PackageStoreComputeProperties};
@@ -46,15 +46,7 @@ impl CompilationContext {
     }
 
     pub fn update(&mut self, source: &str) {
-        let increment = self
-            .compiler
-            .compile_fragments_fail_fast("rca-test", source)
-            .expect("code should compile");
-        let package_id = map_hir_package_to_fir(self.compiler.package_id());
-        let fir_package = self.fir_store.get_mut(package_id);
-        self.lowerer
-            .lower_and_update_package(fir_package, &increment.hir);
-        self.compiler.update(increment);
+        let package_id = self.lower_fragments(source);
 
         // Clear the compute properties of the package to update.
         let package_compute_properties = self.compute_properties.get_mut(package_id);
@@ -65,6 +57,20 @@ impl CompilationContext {
         );
         self.compute_properties = analyzer.analyze_package(package_id);
     }
+
+    /// Compiles and lowers the fragments into the FIR package store without analyzing them.
+    pub fn lower_fragments(&mut self, source: &str) -> PackageId {
+        let increment = self
+            .compiler
+            .compile_fragments_fail_fast("rca-test", source)
+            .expect("code should compile");
+        let package_id = map_hir_package_to_fir(self.compiler.package_id());
+        let fir_package = self.fir_store.get_mut(package_id);
+        self.lowerer
+            .lower_and_update_package(fir_package, &increment.hir);
+        self.compiler.update(increment);
+        package_id
+    }
 }
 
 impl Default for CompilationContext {
@@ -282,3 +282,48 @@ fn check_rca_for_dynamic_big_int() {
         ],
     );
 }
+
+#[test]
+fn check_rca_for_tuple_with_classical_and_dynamic_elements() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        use q = Qubit();
+        (0, M(q))"#,
+    );
+    let package_store_compute_properties = compilation_context.get_compute_properties();
+    check_last_statement_compute_properties(
+        package_store_compute_properties,
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(0x0)
+                    value_kind: Tuple(Element(Static), Element(Dynamic))
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_tuple_reshaped_from_dynamic_result_array() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        use qs = Qubit[2];
+        let results = [M(qs[0]), M(qs[1])];
+        (results[0], results[1])"#,
+    );
+    let package_store_compute_properties = compilation_context.get_compute_properties();
+    check_last_statement_compute_properties(
+        package_store_compute_properties,
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(0x0)
+                    value_kind: Tuple(Element(Dynamic), Element(Dynamic))
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+}
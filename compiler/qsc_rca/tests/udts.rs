@@ -6,6 +6,8 @@
 pub mod test_utils;
 
 use expect_test::expect;
+use qsc_fir::fir::ExprKind;
+use qsc_rca::Analyzer;
 use test_utils::{check_last_statement_compute_properties, CompilationContext};
 
 #[test]
@@ -99,3 +101,83 @@ fn check_rca_for_udt_field_update_with_dynamic_value() {
         ],
     );
 }
+
+#[test]
+fn check_rca_for_udt_field_update_with_dynamic_value_leaves_other_fields_static() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        open Microsoft.Quantum.Math;
+        mutable c = Complex(0.0, 0.0);
+        use q = Qubit();
+        let i = M(q) == Zero ? 0.0 | 1.0;
+        set c w/= Imag <- i;
+        c::Real"#,
+    );
+    let package_store_compute_properties = compilation_context.get_compute_properties();
+    check_last_statement_compute_properties(
+        package_store_compute_properties,
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Classical
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_udt_field_access_after_field_update_with_dynamic_value() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        open Microsoft.Quantum.Math;
+        mutable c = Complex(0.0, 0.0);
+        use q = Qubit();
+        let i = M(q) == Zero ? 0.0 | 1.0;
+        set c w/= Imag <- i;
+        c::Imag"#,
+    );
+    let package_store_compute_properties = compilation_context.get_compute_properties();
+    check_last_statement_compute_properties(
+        package_store_compute_properties,
+        &expect![
+            r#"
+            ApplicationsGeneratorSet:
+                inherent: Quantum: QuantumProperties:
+                    runtime_features: RuntimeFeatureFlags(UseOfDynamicBool | UseOfDynamicDouble)
+                    value_kind: Element(Dynamic)
+                dynamic_param_applications: <empty>"#
+        ],
+    );
+}
+
+#[test]
+fn check_rca_for_standalone_field_access_of_local_bound_outside_of_expression() {
+    let mut compilation_context = CompilationContext::default();
+    compilation_context.update(
+        r#"
+        open Microsoft.Quantum.Math;
+        function Foo(c : Complex) : Double {
+            c::Real
+        }"#,
+    );
+    let package_id = compilation_context
+        .fir_store
+        .iter()
+        .map(|(package_id, _)| package_id)
+        .max()
+        .expect("at least one package should exist");
+    let package = compilation_context.fir_store.get(package_id);
+    let field_expr_id = package
+        .exprs
+        .iter()
+        .find_map(|(expr_id, expr)| matches!(expr.kind, ExprKind::Field(..)).then_some(expr_id))
+        .expect("field expression should exist");
+    let mut analyzer = Analyzer::init_with_compute_properties(
+        &compilation_context.fir_store,
+        compilation_context.get_compute_properties().clone(),
+    );
+    let compute_kind = analyzer.analyze_expr_standalone(package_id, field_expr_id);
+    expect!["Classical"].assert_eq(&compute_kind.to_string());
+}